@@ -0,0 +1,5 @@
+-- Optional track/category for multi-track venues
+
+ALTER TABLE submissions ADD COLUMN IF NOT EXISTS category TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_submissions_category ON submissions(category);
@@ -0,0 +1,3 @@
+-- Issue date for certificates, used to compute expiry on verification
+
+ALTER TABLE submissions ADD COLUMN IF NOT EXISTS certificate_issued_at TIMESTAMPTZ;
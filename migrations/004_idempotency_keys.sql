@@ -0,0 +1,7 @@
+-- Idempotency keys for POST /api/submit retries
+
+CREATE TABLE IF NOT EXISTS idempotency_keys (
+    idempotency_key TEXT PRIMARY KEY,
+    submission_id TEXT NOT NULL REFERENCES submissions(submission_id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
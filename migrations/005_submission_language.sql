@@ -0,0 +1,4 @@
+-- Detected paper language and free-form flags for operator attention
+
+ALTER TABLE submissions ADD COLUMN IF NOT EXISTS detected_language TEXT;
+ALTER TABLE submissions ADD COLUMN IF NOT EXISTS flags TEXT[] NOT NULL DEFAULT '{}';
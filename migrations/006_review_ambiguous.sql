@@ -0,0 +1,3 @@
+-- Marks reviews whose decision had to be inferred without a FINAL DECISION marker
+
+ALTER TABLE review_results ADD COLUMN IF NOT EXISTS ambiguous BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,3 @@
+-- Content type declared by the client for the uploaded paper
+
+ALTER TABLE submissions ADD COLUMN IF NOT EXISTS content_type TEXT;
@@ -0,0 +1,3 @@
+-- Structured JSON review (scores, decision, summary, suggestions) when the model supplied one
+
+ALTER TABLE review_results ADD COLUMN IF NOT EXISTS structured_review JSONB;
@@ -0,0 +1,6 @@
+-- Outcome documented by the certificate: accepted or minor_revision
+
+ALTER TABLE submissions ADD COLUMN IF NOT EXISTS certificate_kind TEXT;
+
+UPDATE submissions SET certificate_kind = 'accepted'
+WHERE certificate_filename IS NOT NULL AND certificate_kind IS NULL;
@@ -0,0 +1,3 @@
+-- SHA-256 of the per-submission author token; NULL for submissions created before tokens
+
+ALTER TABLE submissions ADD COLUMN IF NOT EXISTS author_token_hash TEXT;
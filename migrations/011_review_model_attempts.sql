@@ -0,0 +1,4 @@
+-- Models tried for each review, in order, and (opt-in) the per-request attempt log
+
+ALTER TABLE review_results ADD COLUMN IF NOT EXISTS model_sequence TEXT[] NOT NULL DEFAULT '{}';
+ALTER TABLE review_results ADD COLUMN IF NOT EXISTS attempt_log JSONB;
@@ -0,0 +1,4 @@
+-- Claude API tokens spent on each submission's reviews (STORE_TOKEN_USAGE)
+
+ALTER TABLE submissions ADD COLUMN IF NOT EXISTS input_tokens BIGINT NOT NULL DEFAULT 0;
+ALTER TABLE submissions ADD COLUMN IF NOT EXISTS output_tokens BIGINT NOT NULL DEFAULT 0;
@@ -0,0 +1,6 @@
+-- Links a resubmitted (revised) paper to the submission it revises
+
+ALTER TABLE submissions ADD COLUMN IF NOT EXISTS parent_submission_id TEXT
+    REFERENCES submissions(submission_id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS idx_submissions_parent_submission_id ON submissions(parent_submission_id);
@@ -0,0 +1,3 @@
+-- When a submission's results stop being accessible (SUBMISSION_TTL_DAYS); NULL never expires
+
+ALTER TABLE submissions ADD COLUMN IF NOT EXISTS expires_at TIMESTAMPTZ;
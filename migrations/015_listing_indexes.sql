@@ -0,0 +1,13 @@
+-- Indexes for the existing query patterns. review_results(submission_id) and
+-- submissions(submission_id) are already indexed by 001_initial_schema.
+
+-- Per-reviewer lookups and reviewer-ordered listings
+CREATE INDEX IF NOT EXISTS idx_review_results_submission_reviewer
+    ON review_results(submission_id, reviewer_name, created_at);
+
+-- Newest-first submission listings
+CREATE INDEX IF NOT EXISTS idx_submissions_created_at ON submissions(created_at DESC);
+
+-- Duplicate-title lookups; must match find_submission_by_normalized_title
+CREATE INDEX IF NOT EXISTS idx_submissions_normalized_title
+    ON submissions ((btrim(regexp_replace(lower(paper_title), '[^[:alnum:]]+', ' ', 'g'))));
@@ -0,0 +1,3 @@
+-- Mean pairwise similarity of a submission's reviews, set when processing completes
+
+ALTER TABLE submissions ADD COLUMN IF NOT EXISTS review_similarity DOUBLE PRECISION;
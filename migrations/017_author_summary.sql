@@ -0,0 +1,3 @@
+-- Plain-language explanation of the outcome for authors (AUTHOR_SUMMARY)
+
+ALTER TABLE submissions ADD COLUMN IF NOT EXISTS author_summary TEXT;
@@ -0,0 +1,3 @@
+-- Retried reviews are kept as history instead of being deleted
+
+ALTER TABLE review_results ADD COLUMN IF NOT EXISTS superseded BOOLEAN NOT NULL DEFAULT false;
@@ -0,0 +1,17 @@
+-- Durable queue of submissions awaiting review, claimed by the review workers
+
+CREATE TABLE IF NOT EXISTS review_jobs (
+    id SERIAL PRIMARY KEY,
+    submission_id TEXT UNIQUE NOT NULL REFERENCES submissions(submission_id) ON DELETE CASCADE,
+    -- queued, running, done or failed
+    status TEXT NOT NULL DEFAULT 'queued',
+    attempts INTEGER NOT NULL DEFAULT 0,
+    next_run_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    -- A running job whose lock has lapsed is claimed again
+    locked_until TIMESTAMPTZ,
+    last_error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_review_jobs_status_next_run_at ON review_jobs(status, next_run_at);
@@ -0,0 +1,3 @@
+-- Key reasons behind a non-accepting decision, from the review's weaknesses section
+
+ALTER TABLE review_results ADD COLUMN IF NOT EXISTS reasons TEXT[] NOT NULL DEFAULT '{}';
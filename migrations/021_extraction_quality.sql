@@ -0,0 +1,3 @@
+-- Word-likeness score of the extracted paper text (EXTRACTION_QUALITY_THRESHOLD)
+
+ALTER TABLE submissions ADD COLUMN IF NOT EXISTS extraction_quality DOUBLE PRECISION;
@@ -0,0 +1,3 @@
+-- Why an earned certificate could not be generated (e.g. missing fonts)
+
+ALTER TABLE submissions ADD COLUMN IF NOT EXISTS certificate_error TEXT;
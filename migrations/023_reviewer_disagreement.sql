@@ -0,0 +1,4 @@
+-- Split accept/reject outcomes and their escalation (DISAGREEMENT_POLICY)
+
+ALTER TABLE submissions ADD COLUMN IF NOT EXISTS reviewer_disagreement BOOLEAN NOT NULL DEFAULT false;
+ALTER TABLE submissions ADD COLUMN IF NOT EXISTS needs_human_review BOOLEAN NOT NULL DEFAULT false;
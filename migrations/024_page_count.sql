@@ -0,0 +1,3 @@
+-- Pages in the uploaded PDF (MIN_PAGE_COUNT / MAX_PAGE_COUNT)
+
+ALTER TABLE submissions ADD COLUMN IF NOT EXISTS page_count INTEGER;
@@ -0,0 +1,5 @@
+-- When the retention sweep removed a submission's upload or result files
+-- (UPLOAD_RETENTION_DAYS / RESULTS_RETENTION_DAYS)
+
+ALTER TABLE submissions ADD COLUMN IF NOT EXISTS upload_deleted_at TIMESTAMPTZ;
+ALTER TABLE submissions ADD COLUMN IF NOT EXISTS results_deleted_at TIMESTAMPTZ;
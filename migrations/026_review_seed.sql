@@ -0,0 +1,3 @@
+-- REVIEW_SEED in effect when each review was generated
+
+ALTER TABLE review_results ADD COLUMN IF NOT EXISTS seed BIGINT;
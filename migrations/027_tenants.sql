@@ -0,0 +1,4 @@
+-- Venue each submission belongs to when several share one instance
+
+ALTER TABLE submissions ADD COLUMN IF NOT EXISTS tenant_id TEXT;
+CREATE INDEX IF NOT EXISTS idx_submissions_tenant_id ON submissions (tenant_id);
@@ -0,0 +1,16 @@
+-- Completed reviews reused for identical papers under the same prompt and
+-- model (REVIEW_CACHE)
+
+CREATE TABLE IF NOT EXISTS review_cache (
+    id SERIAL PRIMARY KEY,
+    content_hash TEXT NOT NULL,
+    prompt_hash TEXT NOT NULL,
+    model TEXT NOT NULL,
+    reviewer_name TEXT NOT NULL,
+    review_text TEXT NOT NULL,
+    structured_review JSONB,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (content_hash, prompt_hash, model, reviewer_name)
+);
+
+ALTER TABLE review_results ADD COLUMN IF NOT EXISTS cached BOOLEAN NOT NULL DEFAULT false;
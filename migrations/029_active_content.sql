@@ -0,0 +1,3 @@
+-- Active PDF features (JavaScript, open actions, ...) found at upload
+
+ALTER TABLE submissions ADD COLUMN IF NOT EXISTS active_content TEXT[] NOT NULL DEFAULT '{}';
@@ -0,0 +1,5 @@
+-- Distance of each review's weighted score from the decision thresholds
+
+ALTER TABLE review_results ADD COLUMN IF NOT EXISTS weighted_score DOUBLE PRECISION;
+ALTER TABLE review_results ADD COLUMN IF NOT EXISTS confidence DOUBLE PRECISION;
+ALTER TABLE submissions ADD COLUMN IF NOT EXISTS decision_confidence DOUBLE PRECISION;
@@ -0,0 +1,80 @@
+// Detection of PDF features that run code or act on their own when the file
+// is opened in a viewer. Text extraction never executes them; the check is
+// for what happens when reviewers or authors download the paper.
+use pdf_extract::{Dictionary, Document, Object};
+use regex::bytes::Regex;
+
+/// Kinds of active content found in `data`, in a fixed order and without
+/// duplicates: `javascript`, `open_action`, `additional_actions`, `launch`
+/// and `embedded_file`. Files the parser rejects are scanned as raw bytes.
+pub fn detect_active_content(data: &[u8]) -> Vec<&'static str> {
+    let mut found = Vec::new();
+    match Document::load_mem(data) {
+        Ok(doc) => {
+            for object in doc.objects.values() {
+                scan_object(object, &mut found);
+            }
+        }
+        Err(_) => scan_bytes(data, &mut found),
+    }
+    KINDS
+        .iter()
+        .copied()
+        .filter(|k| found.contains(k))
+        .collect()
+}
+
+const KINDS: [&str; 5] = [
+    "javascript",
+    "open_action",
+    "additional_actions",
+    "launch",
+    "embedded_file",
+];
+
+fn kind_of_key(key: &[u8]) -> Option<&'static str> {
+    match key {
+        b"JS" | b"JavaScript" => Some("javascript"),
+        b"OpenAction" => Some("open_action"),
+        b"AA" => Some("additional_actions"),
+        b"EmbeddedFiles" | b"EmbeddedFile" => Some("embedded_file"),
+        _ => None,
+    }
+}
+
+fn scan_dict(dict: &Dictionary, found: &mut Vec<&'static str>) {
+    for (key, value) in dict.iter() {
+        if let Some(kind) = kind_of_key(key) {
+            found.push(kind);
+        }
+        // Action dictionaries name their type in /S
+        if key.as_slice() == b"S" {
+            match value.as_name() {
+                Ok(b"JavaScript") => found.push("javascript"),
+                Ok(b"Launch") => found.push("launch"),
+                _ => {}
+            }
+        }
+        scan_object(value, found);
+    }
+}
+
+fn scan_object(object: &Object, found: &mut Vec<&'static str>) {
+    match object {
+        Object::Dictionary(dict) => scan_dict(dict, found),
+        Object::Stream(stream) => scan_dict(&stream.dict, found),
+        Object::Array(items) => items.iter().for_each(|item| scan_object(item, found)),
+        _ => {}
+    }
+}
+
+fn scan_bytes(data: &[u8], found: &mut Vec<&'static str>) {
+    let names =
+        Regex::new(r"/(JavaScript|JS|OpenAction|AA|Launch|EmbeddedFiles?)[\s/<>\[\]()]").unwrap();
+    for caps in names.captures_iter(data) {
+        match &caps[1] {
+            b"Launch" => found.push("launch"),
+            key => found.extend(kind_of_key(key)),
+        }
+    }
+}
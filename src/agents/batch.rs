@@ -0,0 +1,355 @@
+// Message Batches API: reviews queued together are submitted as one batch and
+// polled until it ends, at lower cost and outside the per-request rate limits.
+use reqwest::Client;
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot};
+use tracing::{info, warn};
+
+use super::claude::{ClaudeRequest, TokenUsage};
+use super::error::AgentError;
+use crate::config::Config;
+
+const BATCHES_URL: &str = "https://api.anthropic.com/v1/messages/batches";
+
+/// Requests submitted in one batch at most; more wait for the next window.
+const MAX_BATCH_REQUESTS: usize = 1000;
+
+/// Consecutive failed status polls after which a batch is abandoned.
+const MAX_POLL_FAILURES: u32 = 10;
+
+/// Text and usage of a request that succeeded inside a batch.
+pub struct BatchOutput {
+    pub text: String,
+    pub usage: Option<TokenUsage>,
+}
+
+pub enum BatchReply {
+    /// Too few submissions were queued, or the batch could not be created;
+    /// the caller should send the request directly.
+    Unbatched,
+    Done(Result<BatchOutput, AgentError>),
+}
+
+struct BatchJob {
+    submission_id: String,
+    request: ClaudeRequest,
+    reply: oneshot::Sender<BatchReply>,
+}
+
+/// Collects review requests for `BATCH_WINDOW_SECS` and submits them as one
+/// batch when they span at least `BATCH_MIN_SUBMISSIONS` submissions.
+#[derive(Clone)]
+pub struct BatchCoordinator {
+    jobs: mpsc::UnboundedSender<BatchJob>,
+}
+
+impl BatchCoordinator {
+    /// Spawns the coordinator task; it runs for the life of the process.
+    pub fn start(config: &Config, client: Client) -> Self {
+        let (jobs, receiver) = mpsc::unbounded_channel();
+        let api = BatchApi {
+            client,
+            api_key: config.claude_api_key.clone(),
+            anthropic_version: config.anthropic_version.clone(),
+            extra_headers: config.claude_extra_headers.clone(),
+            poll_interval: Duration::from_secs(config.batch_poll_secs),
+        };
+        tokio::spawn(collect(
+            receiver,
+            api,
+            Duration::from_secs(config.batch_window_secs),
+            config.batch_min_submissions,
+        ));
+        Self { jobs }
+    }
+
+    /// Queues `request` and waits for its batch to finish.
+    pub(super) async fn submit(&self, submission_id: &str, request: ClaudeRequest) -> BatchReply {
+        let (reply, receiver) = oneshot::channel();
+        let job = BatchJob {
+            submission_id: submission_id.to_string(),
+            request,
+            reply,
+        };
+        if self.jobs.send(job).is_err() {
+            return BatchReply::Unbatched;
+        }
+        receiver.await.unwrap_or(BatchReply::Unbatched)
+    }
+}
+
+/// Gathers jobs arriving within `window` of the first one and hands each
+/// group to its own task, so the next window opens while a batch runs.
+async fn collect(
+    mut receiver: mpsc::UnboundedReceiver<BatchJob>,
+    api: BatchApi,
+    window: Duration,
+    min_submissions: usize,
+) {
+    while let Some(first) = receiver.recv().await {
+        let mut jobs = vec![first];
+        let deadline = tokio::time::Instant::now() + window;
+        while jobs.len() < MAX_BATCH_REQUESTS {
+            match tokio::time::timeout_at(deadline, receiver.recv()).await {
+                Ok(Some(job)) => jobs.push(job),
+                _ => break,
+            }
+        }
+
+        let submissions: HashSet<&str> = jobs.iter().map(|j| j.submission_id.as_str()).collect();
+        if submissions.len() < min_submissions {
+            for job in jobs {
+                let _ = job.reply.send(BatchReply::Unbatched);
+            }
+            continue;
+        }
+        tokio::spawn(run_batch(api.clone(), jobs));
+    }
+}
+
+async fn run_batch(api: BatchApi, jobs: Vec<BatchJob>) {
+    let (requests, replies): (Vec<_>, Vec<_>) = jobs
+        .into_iter()
+        .enumerate()
+        .map(|(i, job)| {
+            let custom_id = format!("review-{}", i);
+            (
+                serde_json::json!({ "custom_id": custom_id, "params": job.request }),
+                (custom_id, job.reply),
+            )
+        })
+        .unzip();
+
+    let batch = match api.create(&requests).await {
+        Ok(batch) => batch,
+        Err(e) => {
+            warn!(
+                "Failed to create review batch ({}); sending requests directly",
+                e
+            );
+            for (_, reply) in replies {
+                let _ = reply.send(BatchReply::Unbatched);
+            }
+            return;
+        }
+    };
+    info!(
+        "Submitted batch {} with {} review requests",
+        batch.id,
+        requests.len()
+    );
+
+    let mut results = match api.wait_for_results(batch).await {
+        Ok(results) => results,
+        Err(e) => {
+            warn!("Review batch failed: {}", e);
+            for (_, reply) in replies {
+                let _ = reply.send(BatchReply::Done(Err(AgentError::Request(e.clone()))));
+            }
+            return;
+        }
+    };
+    for (custom_id, reply) in replies {
+        let result = results.remove(&custom_id).unwrap_or_else(|| {
+            Err(AgentError::Request(
+                "missing from batch results".to_string(),
+            ))
+        });
+        let _ = reply.send(BatchReply::Done(result));
+    }
+}
+
+#[derive(Clone)]
+struct BatchApi {
+    client: Client,
+    api_key: String,
+    anthropic_version: String,
+    extra_headers: reqwest::header::HeaderMap,
+    poll_interval: Duration,
+}
+
+#[derive(Debug, Deserialize)]
+struct MessageBatch {
+    id: String,
+    processing_status: String,
+    results_url: Option<String>,
+}
+
+/// One line of a batch's JSONL results file.
+#[derive(Debug, Deserialize)]
+struct BatchResultLine {
+    custom_id: String,
+    result: BatchResult,
+}
+
+#[derive(Debug, Deserialize)]
+struct BatchResult {
+    /// `succeeded`, `errored`, `canceled` or `expired`.
+    #[serde(rename = "type")]
+    result_type: String,
+    message: Option<BatchMessage>,
+    error: Option<BatchErrorEnvelope>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BatchMessage {
+    #[serde(default)]
+    content: Vec<ContentBlock>,
+    usage: Option<TokenUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ContentBlock {
+    #[serde(rename = "type")]
+    block_type: String,
+    text: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BatchErrorEnvelope {
+    error: Option<BatchErrorDetail>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BatchErrorDetail {
+    #[serde(rename = "type")]
+    error_type: String,
+    message: Option<String>,
+}
+
+impl BatchApi {
+    fn request(&self, method: reqwest::Method, url: &str) -> reqwest::RequestBuilder {
+        self.client
+            .request(method, url)
+            .header("x-api-key", &self.api_key)
+            .header("anthropic-version", &self.anthropic_version)
+            .headers(self.extra_headers.clone())
+    }
+
+    async fn create(&self, requests: &[serde_json::Value]) -> Result<MessageBatch, String> {
+        let response = self
+            .request(reqwest::Method::POST, BATCHES_URL)
+            .json(&serde_json::json!({ "requests": requests }))
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        read_json(response).await
+    }
+
+    async fn get(&self, batch_id: &str) -> Result<MessageBatch, String> {
+        let response = self
+            .request(
+                reqwest::Method::GET,
+                &format!("{}/{}", BATCHES_URL, batch_id),
+            )
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        read_json(response).await
+    }
+
+    /// Polls until the batch ends, then downloads its results by custom id.
+    async fn wait_for_results(
+        &self,
+        mut batch: MessageBatch,
+    ) -> Result<HashMap<String, Result<BatchOutput, AgentError>>, String> {
+        let mut failures = 0;
+        while batch.processing_status != "ended" {
+            tokio::time::sleep(self.poll_interval).await;
+            match self.get(&batch.id).await {
+                Ok(latest) => {
+                    batch = latest;
+                    failures = 0;
+                }
+                Err(e) => {
+                    failures += 1;
+                    warn!(
+                        "Polling batch {} failed ({}/{}): {}",
+                        batch.id, failures, MAX_POLL_FAILURES, e
+                    );
+                    if failures >= MAX_POLL_FAILURES {
+                        return Err(format!("gave up polling batch {}: {}", batch.id, e));
+                    }
+                }
+            }
+        }
+        info!("Batch {} ended", batch.id);
+
+        let url = batch
+            .results_url
+            .ok_or_else(|| format!("batch {} ended without results", batch.id))?;
+        let response = self
+            .request(reqwest::Method::GET, &url)
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        if !response.status().is_success() {
+            return Err(format!("results download failed ({})", response.status()));
+        }
+        let body = response.text().await.map_err(|e| e.to_string())?;
+
+        Ok(body
+            .lines()
+            .filter_map(|line| serde_json::from_str::<BatchResultLine>(line).ok())
+            .map(|line| (line.custom_id, line.result.into_output()))
+            .collect())
+    }
+}
+
+impl BatchResult {
+    fn into_output(self) -> Result<BatchOutput, AgentError> {
+        match self.result_type.as_str() {
+            "succeeded" => {
+                let message = self.message.ok_or(AgentError::EmptyResponse)?;
+                let text: String = message
+                    .content
+                    .iter()
+                    .filter(|b| b.block_type == "text")
+                    .filter_map(|b| b.text.as_deref())
+                    .collect();
+                if text.is_empty() {
+                    return Err(AgentError::EmptyResponse);
+                }
+                Ok(BatchOutput {
+                    text,
+                    usage: message.usage,
+                })
+            }
+            "errored" => {
+                let detail = self.error.and_then(|e| e.error);
+                let (error_type, message) = match detail {
+                    Some(d) => (d.error_type, d.message.unwrap_or_default()),
+                    None => (String::new(), "batched request failed".to_string()),
+                };
+                Err(match error_type.as_str() {
+                    "authentication_error" | "permission_error" => AgentError::Auth(message),
+                    "rate_limit_error" => AgentError::RateLimited(message),
+                    "invalid_request_error" => AgentError::Api {
+                        status: 400,
+                        message,
+                    },
+                    "not_found_error" => AgentError::Api {
+                        status: 404,
+                        message,
+                    },
+                    _ => AgentError::Api {
+                        status: 500,
+                        message,
+                    },
+                })
+            }
+            other => Err(AgentError::Request(format!("batched request {}", other))),
+        }
+    }
+}
+
+async fn read_json(response: reqwest::Response) -> Result<MessageBatch, String> {
+    let status = response.status();
+    let text = response.text().await.map_err(|e| e.to_string())?;
+    if !status.is_success() {
+        return Err(format!("{}: {}", status, text));
+    }
+    serde_json::from_str(&text).map_err(|e| e.to_string())
+}
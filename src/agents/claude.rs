@@ -3,18 +3,20 @@ use serde::{Deserialize, Serialize};
 use std::time::Duration;
 use tracing::{info, warn};
 
+use super::batch::{BatchCoordinator, BatchReply};
+use super::error::AgentError;
+use super::injection::{untrusted_paper_notice, wrap_paper_text};
+use super::preprocess::Preprocessing;
+use super::structured::{Review, STRUCTURED_OUTPUT_INSTRUCTIONS};
+use crate::config::{Config, ReviewFormat};
+
 const REVIEW_PROMPT: &str = r#"You are an academic reviewer evaluating a research paper. Write your review in third person, 
 starting with "The reviewer has evaluated this paper based on the given criteria and arrived 
 at the following conclusions:"
 
 Evaluate each criterion from 0-100%:
 
-1. Methodology (20% of total): Evaluate the research methodology, experimental design, and validation
-2. Novelty (20% of total): Assess the innovation and original contribution to the field
-3. Technical Depth (15% of total): Examine technical accuracy, depth of analysis, and rigor
-4. Clarity (15% of total): Evaluate writing quality, organization, and presentation
-5. Literature Review (15% of total): Assess coverage and understanding of related work
-6. Impact (15% of total): Consider potential influence on the field and practical applications
+{criteria}
 
 For each criterion, the reviewer should:
 - Begin with positive aspects before addressing issues
@@ -26,10 +28,10 @@ For each criterion, the reviewer should:
 The reviewer will calculate the weighted final score based on the criteria weights.
 
 Recommendation threshold (more lenient standards):
-- Accept (>60%): Good paper that contributes to the field
-- Accept with Minor Revision (50-60%): Promising work needing minor improvements
-- Accept with Major Revision (40-50%): Valuable contribution requiring significant changes
-- Reject (<40%): Does not meet basic publication standards
+- Accept (>{accept}%): Good paper that contributes to the field
+- Accept with Minor Revision ({minor}-{accept}%): Promising work needing minor improvements
+- Accept with Major Revision ({major}-{minor}%): Valuable contribution requiring significant changes
+- Reject (<{major}%): Does not meet basic publication standards
 
 IMPORTANT: The conference values inclusivity and diverse perspectives. Papers should generally 
 be accepted unless there are serious, unfixable flaws. The default stance should be acceptance rather than rejection.
@@ -46,7 +48,84 @@ The review concludes with:
 
 Always maintain third-person perspective throughout the review."#;
 
-const CLAUDE_MODELS: &[&str] = &[
+/// `REVIEW_PROMPT` with the configured criteria and score thresholds
+/// filled in.
+fn review_prompt(config: &Config) -> String {
+    let criteria = config
+        .review_criteria
+        .iter()
+        .enumerate()
+        .map(|(i, c)| {
+            format!(
+                "{}. {} ({}% of total): {}",
+                i + 1,
+                c.name,
+                c.weight,
+                c.description
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    let thresholds = &config.decision_thresholds;
+    let mut prompt = REVIEW_PROMPT
+        .replace("{criteria}", &criteria)
+        .replace("{accept}", &thresholds.accept.to_string())
+        .replace("{minor}", &thresholds.minor_revision.to_string())
+        .replace("{major}", &thresholds.major_revision.to_string());
+    if config.review_format == ReviewFormat::Markdown {
+        prompt.push_str(MARKDOWN_INSTRUCTIONS);
+    }
+    if let Some(language) = &config.review_language {
+        // The decision marker and JSON stay in English so they still parse
+        prompt.push_str(&format!(
+            "\n\nWrite the review in {}. Keep the FINAL DECISION line exactly as specified \
+             above, in English (for example \"FINAL DECISION: **ACCEPTED**\"), and keep the \
+             JSON field names and decision values in English.",
+            language
+        ));
+    }
+    prompt
+}
+
+const MARKDOWN_INSTRUCTIONS: &str = "\n\nFormat the review in Markdown: a `##` heading for each \
+section, bullet lists for strengths, weaknesses and suggestions, and **bold** criterion names \
+with their scores. Do not use links, images, tables or HTML.";
+
+const AUTHOR_SUMMARY_PROMPT: &str = "You explain peer review outcomes to the authors of a \
+research paper, many of whom are early in their careers. From the outcome and the reviewers' \
+summaries below, write two or three short sentences in plain, friendly language saying what \
+was decided and the main things to work on. Avoid jargon, scores and reviewer names, and do not \
+add points the reviewers did not make. Reply with the summary only.";
+
+const REVIEW_SUMMARY_PROMPT: &str = "Below is a peer review of a research paper. Summarize \
+the reviewer's overall assessment in two or three sentences: the main strengths, the main \
+weaknesses and the decision. Do not add points the review does not make. Reply with the \
+summary only.";
+
+/// Output allowance for a regenerated review summary.
+const REVIEW_SUMMARY_MAX_TOKENS: u32 = 200;
+
+/// `REVIEW_SUMMARY_PROMPT` in the configured review language.
+fn review_summary_prompt(config: &Config) -> String {
+    match &config.review_language {
+        Some(language) => format!("{} Write it in {}.", REVIEW_SUMMARY_PROMPT, language),
+        None => REVIEW_SUMMARY_PROMPT.to_string(),
+    }
+}
+
+/// `AUTHOR_SUMMARY_PROMPT` in the configured review language.
+fn author_summary_prompt(config: &Config) -> String {
+    match &config.review_language {
+        Some(language) => format!("{} Write it in {}.", AUTHOR_SUMMARY_PROMPT, language),
+        None => AUTHOR_SUMMARY_PROMPT.to_string(),
+    }
+}
+
+/// Output allowance for an author summary.
+const AUTHOR_SUMMARY_MAX_TOKENS: u32 = 300;
+
+/// Models a review may run on, in fallback order.
+pub const CLAUDE_MODELS: &[&str] = &[
     "claude-3-5-sonnet-20240620",
     "claude-3-opus-20240229",
     "claude-3-sonnet-20240229",
@@ -54,11 +133,16 @@ const CLAUDE_MODELS: &[&str] = &[
 ];
 
 #[derive(Debug, Serialize)]
-struct ClaudeRequest {
+pub(super) struct ClaudeRequest {
     model: String,
     max_tokens: u32,
     system: String,
     messages: Vec<Message>,
+    /// Left out for batch requests, which cannot stream.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    stream: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
 }
 
 #[derive(Debug, Serialize)]
@@ -67,42 +151,162 @@ struct Message {
     content: String,
 }
 
+/// One server-sent event from the streaming messages API. Only the fields
+/// needed to accumulate text and detect the end of the message are read.
+#[derive(Debug, Deserialize)]
+struct StreamEvent {
+    #[serde(rename = "type")]
+    event_type: String,
+    delta: Option<StreamDelta>,
+    error: Option<ClaudeError>,
+    /// Set on `message_start`, carrying the input token count.
+    message: Option<StreamMessage>,
+    /// Set on `message_delta`, carrying the cumulative output token count.
+    usage: Option<TokenUsage>,
+}
+
 #[derive(Debug, Deserialize)]
-struct ClaudeResponse {
-    content: Vec<ContentBlock>,
+struct StreamMessage {
+    usage: Option<TokenUsage>,
+}
+
+/// Tokens billed for a request, as reported by the API.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct TokenUsage {
+    #[serde(default)]
+    pub input_tokens: u64,
+    #[serde(default)]
+    pub output_tokens: u64,
+}
+
+impl TokenUsage {
+    fn add(&mut self, other: TokenUsage) {
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+    }
 }
 
 #[derive(Debug, Deserialize)]
-struct ContentBlock {
+struct StreamDelta {
     #[serde(rename = "type")]
-    block_type: String,
+    delta_type: Option<String>,
     text: Option<String>,
 }
 
+/// A stream that ended before `message_stop`, with whatever text arrived.
+struct StreamFailure {
+    partial: String,
+    message: String,
+}
+
 #[derive(Debug, Deserialize)]
 struct ClaudeError {
-    #[serde(rename = "type")]
-    error_type: Option<String>,
     message: Option<String>,
 }
 
+const DEFAULT_MAX_TOKENS: u32 = 4000;
+
+/// One request to the messages API and how it ended.
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelAttempt {
+    pub model: String,
+    /// `None` when the attempt produced the review.
+    pub error: Option<String>,
+    /// Absent when the request failed before the API reported usage.
+    pub usage: Option<TokenUsage>,
+}
+
 pub struct ClaudeAgent {
     client: Client,
     api_key: String,
     model_index: usize,
+    max_words: Option<u32>,
+    anthropic_version: String,
+    extra_headers: reqwest::header::HeaderMap,
+    /// Base review prompt, built once from the config.
+    review_prompt: String,
+    author_summary_prompt: String,
+    review_summary_prompt: String,
+    /// `SUMMARY_MODEL`, used by [`ClaudeAgent::summarize_review`].
+    summary_model: String,
+    preprocessing: Preprocessing,
+    paper_tag: String,
+    /// `REVIEW_SEED`; reviews run at temperature 0 when set.
+    seed: Option<i64>,
+    /// `RETRY_TEMPERATURES`: temperature of the first, second... retry.
+    retry_temperatures: Vec<f32>,
+    retry_decision_reminder: bool,
+    /// Reviewer retries so far; see [`ClaudeAgent::prepare_retry`].
+    retry: usize,
+    log_token_usage: bool,
+    attempts: Vec<ModelAttempt>,
 }
 
 impl ClaudeAgent {
-    pub fn new(api_key: String) -> Self {
-        let client = Client::builder()
-            .timeout(Duration::from_secs(120))
-            .build()
-            .expect("Failed to create HTTP client");
-
+    pub fn new(config: &Config, client: Client) -> Self {
         Self {
             client,
-            api_key,
+            api_key: config.claude_api_key.clone(),
             model_index: 0,
+            max_words: config.review_max_words,
+            anthropic_version: config.anthropic_version.clone(),
+            extra_headers: config.claude_extra_headers.clone(),
+            review_prompt: review_prompt(config),
+            author_summary_prompt: author_summary_prompt(config),
+            review_summary_prompt: review_summary_prompt(config),
+            summary_model: config.summary_model.clone(),
+            preprocessing: config.preprocessing,
+            paper_tag: config.paper_tag.clone(),
+            seed: config.review_seed,
+            retry_temperatures: config.retry_temperatures.clone(),
+            retry_decision_reminder: config.retry_decision_reminder,
+            retry: 0,
+            log_token_usage: config.log_token_usage,
+            attempts: Vec::new(),
+        }
+    }
+
+    fn system_prompt(&self) -> String {
+        let prompt = system_prompt(&self.review_prompt, self.max_words, &self.paper_tag);
+        if self.retry > 0 && self.retry_decision_reminder {
+            format!("{}\n\n{}", prompt, RETRY_DECISION_REMINDER)
+        } else {
+            prompt
+        }
+    }
+
+    /// Marks the next review as a retry of an unusable one, moving along the
+    /// `RETRY_TEMPERATURES` schedule (its last entry repeats).
+    pub fn prepare_retry(&mut self) {
+        self.retry += 1;
+    }
+
+    fn temperature(&self) -> Option<f32> {
+        match self.retry {
+            0 => self.seed.map(|_| 0.0),
+            retry => self
+                .retry_temperatures
+                .get(retry - 1)
+                .or(self.retry_temperatures.last())
+                .copied()
+                .or(self.seed.map(|_| 0.0)),
+        }
+    }
+
+    fn max_tokens(&self) -> u32 {
+        max_tokens_for(self.max_words)
+    }
+
+    /// Starts from `model` instead of the first model in the list, still
+    /// falling back down the list from there. Returns false for a model
+    /// outside the list, leaving the agent unchanged.
+    pub fn start_with_model(&mut self, model: &str) -> bool {
+        match CLAUDE_MODELS.iter().position(|m| *m == model) {
+            Some(index) => {
+                self.model_index = index;
+                true
+            }
+            None => false,
         }
     }
 
@@ -110,97 +314,233 @@ impl ClaudeAgent {
         CLAUDE_MODELS[self.model_index]
     }
 
-    pub fn extract_text_from_pdf(&self, pdf_path: &str) -> Result<String, String> {
-        let text = pdf_extract::extract_text(pdf_path)
-            .map_err(|e| format!("PDF extraction error: {}", e))?;
+    /// Every request made by this agent, in order.
+    pub fn attempts(&self) -> &[ModelAttempt] {
+        &self.attempts
+    }
 
-        if text.trim().len() < 100 {
-            return Err(format!(
-                "PDF appears empty or has insufficient text ({} chars)",
-                text.len()
-            ));
+    /// Models tried, in order, without consecutive repeats.
+    pub fn model_sequence(&self) -> Vec<String> {
+        let mut sequence: Vec<String> = Vec::new();
+        for attempt in &self.attempts {
+            if sequence.last() != Some(&attempt.model) {
+                sequence.push(attempt.model.clone());
+            }
+        }
+        sequence
+    }
+
+    /// Tokens billed across every request this agent made.
+    pub fn token_usage(&self) -> TokenUsage {
+        let mut total = TokenUsage::default();
+        for usage in self.attempts.iter().filter_map(|a| a.usage) {
+            total.add(usage);
+        }
+        total
+    }
+
+    fn record_attempt(&mut self, model: &str, error: Option<String>) {
+        self.record_attempt_with_usage(model, error, None);
+    }
+
+    fn record_attempt_with_usage(
+        &mut self,
+        model: &str,
+        error: Option<String>,
+        usage: Option<TokenUsage>,
+    ) {
+        if self.log_token_usage {
+            match usage {
+                Some(usage) => info!(
+                    "Request to {} used {} input and {} output tokens",
+                    model, usage.input_tokens, usage.output_tokens
+                ),
+                None if error.is_none() => info!("Request to {} reported no token usage", model),
+                None => {}
+            }
+        }
+        self.attempts.push(ModelAttempt {
+            model: model.to_string(),
+            error,
+            usage,
+        });
+    }
+
+    /// Hash of everything but the paper and model that shapes a review
+    /// request (prompt, limits, sampling, wrapping and preprocessing), so a
+    /// cached review is only reused under identical settings.
+    pub fn prompt_hash(&self) -> String {
+        let request =
+            serde_json::to_string(&self.review_request("", "", false)).unwrap_or_default();
+        let preprocessing = (
+            self.preprocessing.strip_boilerplate,
+            self.preprocessing.trim_references,
+        );
+        crate::storage::content_hash(format!("{}{:?}", request, preprocessing).as_bytes())
+    }
+
+    fn review_request(&self, model: &str, paper_text: &str, stream: bool) -> ClaudeRequest {
+        ClaudeRequest {
+            model: model.to_string(),
+            max_tokens: self.max_tokens(),
+            system: self.system_prompt(),
+            messages: vec![Message {
+                role: "user".to_string(),
+                content: wrap_paper_text(&self.preprocessing.apply(paper_text), &self.paper_tag),
+            }],
+            stream,
+            temperature: self.temperature(),
         }
+    }
 
-        Ok(text)
+    /// Queues the review on the current model with `batches`. When the
+    /// coordinator declines it, or the batched request fails, the review is
+    /// sent directly with the usual fallback and retries.
+    pub async fn generate_review_batched(
+        &mut self,
+        batches: &BatchCoordinator,
+        submission_id: &str,
+        paper_text: &str,
+    ) -> Result<Review, AgentError> {
+        let model = self.current_model().to_string();
+        let request = self.review_request(&model, paper_text, false);
+        match batches.submit(submission_id, request).await {
+            BatchReply::Done(Ok(output)) => {
+                info!("Received batched review from model {}", model);
+                self.record_attempt_with_usage(&model, None, output.usage);
+                return Ok(Review::from_model_output(&output.text));
+            }
+            BatchReply::Done(Err(e)) => {
+                warn!(
+                    "Batched review on {} failed ({}); sending it directly",
+                    model, e
+                );
+                self.record_attempt(&model, Some(e.to_string()));
+            }
+            BatchReply::Unbatched => {}
+        }
+        self.generate_review(paper_text).await
     }
 
-    pub async fn generate_review(&mut self, paper_text: &str) -> Result<String, String> {
+    pub async fn generate_review(&mut self, paper_text: &str) -> Result<Review, AgentError> {
         let max_retries = 3;
         let mut retry_count = 0;
         let mut backoff = 2u64;
 
         loop {
             let model = self.current_model().to_string();
-            info!("Generating review with model {} (paper length: {} chars)", model, paper_text.len());
-
-            let body = ClaudeRequest {
-                model: model.clone(),
-                max_tokens: 4000,
-                system: REVIEW_PROMPT.to_string(),
-                messages: vec![Message {
-                    role: "user".to_string(),
-                    content: paper_text.to_string(),
-                }],
-            };
+            info!(
+                "Generating review with model {} (paper length: {} chars)",
+                model,
+                paper_text.len()
+            );
+
+            let body = self.review_request(&model, paper_text, true);
 
             let response = self
                 .client
                 .post("https://api.anthropic.com/v1/messages")
                 .header("x-api-key", &self.api_key)
-                .header("anthropic-version", "2023-06-01")
+                .header("anthropic-version", &self.anthropic_version)
                 .header("content-type", "application/json")
+                .headers(self.extra_headers.clone())
                 .json(&body)
                 .send()
-                .await
-                .map_err(|e| format!("Request failed: {}", e))?;
+                .await;
+            let response = match response {
+                Ok(response) => response,
+                Err(e) => {
+                    let error = AgentError::from(e);
+                    self.record_attempt(&model, Some(error.to_string()));
+                    return Err(error);
+                }
+            };
 
             let status = response.status();
-            let text = response.text().await.map_err(|e| format!("Response read failed: {}", e))?;
 
-            if status.is_success() {
-                let parsed: ClaudeResponse = serde_json::from_str(&text)
-                    .map_err(|e| format!("Parse error: {}", e))?;
-
-                if let Some(block) = parsed.content.first() {
-                    if let Some(ref t) = block.text {
+            let mut usage = None;
+            let error = if status.is_success() {
+                match read_stream(response, &mut usage).await {
+                    Ok(text) if text.is_empty() => AgentError::EmptyResponse,
+                    Ok(text) => {
                         info!("Successfully generated review with model {}", model);
-                        return Ok(t.clone());
+                        self.record_attempt_with_usage(&model, None, usage);
+                        return Ok(Review::from_model_output(&text));
+                    }
+                    // The decision line comes last, so a stream cut after it
+                    // still carries a complete review
+                    Err(failure) if failure.partial.contains("FINAL DECISION") => {
+                        warn!(
+                            "Stream from {} ended early ({}); keeping complete review text",
+                            model, failure.message
+                        );
+                        self.record_attempt_with_usage(&model, None, usage);
+                        return Ok(Review::from_model_output(&failure.partial));
+                    }
+                    Err(failure) => {
+                        warn!(
+                            "Stream from {} interrupted after {} chars: {}",
+                            model,
+                            failure.partial.len(),
+                            failure.message
+                        );
+                        AgentError::Stream(failure.message)
+                    }
+                }
+            } else {
+                let text = match response.text().await {
+                    Ok(text) => text,
+                    Err(e) => {
+                        let error = AgentError::from(e);
+                        self.record_attempt(&model, Some(error.to_string()));
+                        return Err(error);
+                    }
+                };
+
+                if status.as_u16() == 429 {
+                    warn!("Rate limit with model {}", model);
+                    if self.model_index < CLAUDE_MODELS.len() - 1 {
+                        self.record_attempt(&model, Some("rate limited (429)".to_string()));
+                        self.model_index += 1;
+                        retry_count = 0;
+                        continue;
                     }
                 }
-                return Err("No text in response".to_string());
-            }
-
-            // Handle errors
-            let error_msg = text.clone();
-            let error_json: Result<ClaudeError, _> = serde_json::from_str(&text);
 
-            if status.as_u16() == 429 {
-                warn!("Rate limit with model {}", model);
-                if self.model_index < CLAUDE_MODELS.len() - 1 {
-                    self.model_index += 1;
-                    retry_count = 0;
-                    continue;
+                if status.as_u16() == 404 {
+                    warn!("Model not found: {}", model);
+                    if self.model_index < CLAUDE_MODELS.len() - 1 {
+                        self.record_attempt(&model, Some("model not found (404)".to_string()));
+                        self.model_index += 1;
+                        retry_count = 0;
+                        continue;
+                    }
                 }
-            }
 
-            if status.as_u16() == 404 {
-                warn!("Model not found: {}", model);
-                if self.model_index < CLAUDE_MODELS.len() - 1 {
-                    self.model_index += 1;
-                    retry_count = 0;
-                    continue;
+                let message = serde_json::from_str::<ClaudeError>(&text)
+                    .ok()
+                    .and_then(|e| e.message)
+                    .unwrap_or(text);
+                match status.as_u16() {
+                    401 | 403 => AgentError::Auth(message),
+                    429 => AgentError::RateLimited(message),
+                    code => AgentError::Api {
+                        status: code,
+                        message,
+                    },
                 }
-            }
+            };
 
-            if retry_count >= max_retries {
-                return Err(format!(
-                    "AI service error after {} attempts: {}",
-                    max_retries,
-                    error_json
-                        .ok()
-                        .and_then(|e| e.message)
-                        .unwrap_or_else(|| error_msg)
-                ));
+            self.record_attempt_with_usage(&model, Some(error.to_string()), usage);
+            // A bad key or malformed request fails the same way on every retry
+            if retry_count >= max_retries || !error.is_retryable() {
+                warn!(
+                    "Giving up on {} after {} attempts: {}",
+                    model,
+                    retry_count + 1,
+                    error
+                );
+                return Err(error);
             }
 
             retry_count += 1;
@@ -209,8 +549,246 @@ impl ClaudeAgent {
         }
     }
 
-    pub async fn analyze_paper(&mut self, pdf_path: &str) -> Result<String, String> {
-        let paper_text = self.extract_text_from_pdf(pdf_path)?;
-        self.generate_review(&paper_text).await
+    /// Plain-language summary of the outcome for the authors, written from
+    /// the reviewers' decisions and summaries in a single request.
+    pub async fn generate_author_summary(
+        &mut self,
+        outcome: &str,
+        reviews: &[(&str, &str)],
+    ) -> Result<String, AgentError> {
+        let model = self.current_model().to_string();
+        let mut content = format!("Outcome: {}\n", outcome);
+        for (decision, summary) in reviews {
+            content.push_str(&format!(
+                "\nReviewer decision: {}\nSummary: {}\n",
+                decision, summary
+            ));
+        }
+        let system = self.author_summary_prompt.clone();
+        self.complete(&model, system, content, AUTHOR_SUMMARY_MAX_TOKENS)
+            .await
+    }
+
+    /// Writes a fresh short summary of a stored review on `SUMMARY_MODEL`,
+    /// far cheaper than reviewing the paper again.
+    pub async fn summarize_review(&mut self, full_review: &str) -> Result<String, AgentError> {
+        let model = self.summary_model.clone();
+        let system = self.review_summary_prompt.clone();
+        self.complete(
+            &model,
+            system,
+            full_review.to_string(),
+            REVIEW_SUMMARY_MAX_TOKENS,
+        )
+        .await
+    }
+
+    /// One streamed, non-review request, recorded as an attempt.
+    async fn complete(
+        &mut self,
+        model: &str,
+        system: String,
+        content: String,
+        max_tokens: u32,
+    ) -> Result<String, AgentError> {
+        let body = ClaudeRequest {
+            model: model.to_string(),
+            max_tokens,
+            system,
+            messages: vec![Message {
+                role: "user".to_string(),
+                content,
+            }],
+            stream: true,
+            temperature: None,
+        };
+
+        let response = self
+            .client
+            .post("https://api.anthropic.com/v1/messages")
+            .header("x-api-key", &self.api_key)
+            .header("anthropic-version", &self.anthropic_version)
+            .header("content-type", "application/json")
+            .headers(self.extra_headers.clone())
+            .json(&body)
+            .send()
+            .await;
+        let response = match response {
+            Ok(response) => response,
+            Err(e) => {
+                let error = AgentError::from(e);
+                self.record_attempt(model, Some(error.to_string()));
+                return Err(error);
+            }
+        };
+
+        let status = response.status();
+        let mut usage = None;
+        let result = if status.is_success() {
+            match read_stream(response, &mut usage).await {
+                Ok(text) if text.trim().is_empty() => Err(AgentError::EmptyResponse),
+                Ok(text) => Ok(text.trim().to_string()),
+                Err(failure) => Err(AgentError::Stream(failure.message)),
+            }
+        } else {
+            let text = response.text().await.unwrap_or_default();
+            let message = serde_json::from_str::<ClaudeError>(&text)
+                .ok()
+                .and_then(|e| e.message)
+                .unwrap_or(text);
+            Err(match status.as_u16() {
+                401 | 403 => AgentError::Auth(message),
+                429 => AgentError::RateLimited(message),
+                code => AgentError::Api {
+                    status: code,
+                    message,
+                },
+            })
+        };
+        self.record_attempt_with_usage(model, result.as_ref().err().map(|e| e.to_string()), usage);
+        result
+    }
+}
+
+/// Added to the system prompt of retries with `RETRY_DECISION_REMINDER`.
+const RETRY_DECISION_REMINDER: &str = "An earlier review of this paper could not be used \
+because its decision was missing or unclear. End this review with exactly one FINAL DECISION \
+line in the format specified above, and do not hedge between decisions.";
+
+/// The complete system prompt sent with each review request.
+fn system_prompt(review_prompt: &str, max_words: Option<u32>, paper_tag: &str) -> String {
+    let mut prompt = review_prompt.to_string();
+    if let Some(words) = max_words {
+        prompt.push_str(&format!(
+            "\n\nKeep the review under {} words. However short the review, it must still end \
+             with the FINAL DECISION line exactly as specified above.",
+            words
+        ));
+    }
+    format!(
+        "{}\n\n{}\n\n{}",
+        prompt,
+        STRUCTURED_OUTPUT_INSTRUCTIONS,
+        untrusted_paper_notice(paper_tag)
+    )
+}
+
+/// The system prompt reviews currently run with, as `/api/prompt` shows it.
+pub fn active_system_prompt(config: &Config) -> String {
+    system_prompt(
+        &review_prompt(config),
+        config.review_max_words,
+        &config.paper_tag,
+    )
+}
+
+/// Roughly two tokens per requested word leaves headroom for formatting
+/// so the closing FINAL DECISION line is not cut off.
+fn max_tokens_for(max_words: Option<u32>) -> u32 {
+    match max_words {
+        Some(words) => (words.saturating_mul(2) + 200).min(DEFAULT_MAX_TOKENS * 2),
+        None => DEFAULT_MAX_TOKENS,
+    }
+}
+
+/// Approximate tokens one review of `paper_text` consumes: the prompt and
+/// paper at about four characters per token, plus the full output allowance.
+pub fn estimate_review_tokens(config: &Config, paper_text: &str) -> u64 {
+    let input_chars = review_prompt(config).len()
+        + STRUCTURED_OUTPUT_INSTRUCTIONS.len()
+        + untrusted_paper_notice(&config.paper_tag).len()
+        + config.preprocessing.apply(paper_text).len();
+    (input_chars as u64).div_ceil(4) + max_tokens_for(config.review_max_words) as u64
+}
+
+/// Builds the HTTP client shared by every reviewer. Streaming responses can
+/// run long, so the gap between reads is bounded rather than the whole request.
+pub fn build_http_client(config: &Config) -> Result<Client, String> {
+    let mut builder = Client::builder()
+        .connect_timeout(Duration::from_secs(config.http_connect_timeout_secs))
+        .read_timeout(Duration::from_secs(config.http_read_timeout_secs))
+        .pool_max_idle_per_host(config.http_pool_max_idle_per_host);
+    if let Some(proxy) = &config.https_proxy {
+        let proxy =
+            reqwest::Proxy::https(proxy).map_err(|e| format!("Invalid HTTPS_PROXY: {}", e))?;
+        builder = builder.proxy(proxy);
+    }
+    builder
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))
+}
+
+/// Accumulates the text deltas of a streaming messages response until
+/// `message_stop`. Events are separated by blank lines; each carries its
+/// JSON payload on a `data:` line. Token usage is written to `usage` as it
+/// arrives, so it survives an interrupted stream.
+async fn read_stream(
+    mut response: reqwest::Response,
+    usage: &mut Option<TokenUsage>,
+) -> Result<String, StreamFailure> {
+    let mut text = String::new();
+    let mut buffer: Vec<u8> = Vec::new();
+
+    loop {
+        let chunk = match response.chunk().await {
+            Ok(Some(chunk)) => chunk,
+            Ok(None) => {
+                return Err(StreamFailure {
+                    partial: text,
+                    message: "stream ended before message_stop".to_string(),
+                })
+            }
+            Err(e) => {
+                return Err(StreamFailure {
+                    partial: text,
+                    message: format!("stream read failed: {}", e),
+                })
+            }
+        };
+        // Buffer raw bytes so multi-byte characters split across chunks
+        // survive; events always end on a newline boundary
+        buffer.extend(chunk.iter().filter(|b| **b != b'\r'));
+
+        while let Some(end) = buffer.windows(2).position(|w| w == b"\n\n") {
+            let raw_event: Vec<u8> = buffer.drain(..end + 2).collect();
+            let raw_event = String::from_utf8_lossy(&raw_event);
+            for data in raw_event.lines().filter_map(|l| l.strip_prefix("data:")) {
+                let event: StreamEvent = match serde_json::from_str(data.trim()) {
+                    Ok(event) => event,
+                    Err(_) => continue,
+                };
+                match event.event_type.as_str() {
+                    "message_start" => {
+                        if let Some(start) = event.message.and_then(|m| m.usage) {
+                            *usage = Some(start);
+                        }
+                    }
+                    "message_delta" => {
+                        if let Some(delta) = event.usage {
+                            usage.get_or_insert_with(TokenUsage::default).output_tokens =
+                                delta.output_tokens;
+                        }
+                    }
+                    "content_block_delta" => {
+                        if let Some(delta) = event.delta {
+                            if delta.delta_type.as_deref() == Some("text_delta") {
+                                text.push_str(delta.text.as_deref().unwrap_or(""));
+                            }
+                        }
+                    }
+                    "message_stop" => return Ok(text),
+                    "error" => {
+                        return Err(StreamFailure {
+                            partial: text,
+                            message: event
+                                .error
+                                .and_then(|e| e.message)
+                                .unwrap_or_else(|| "stream error".to_string()),
+                        })
+                    }
+                    _ => {}
+                }
+            }
+        }
     }
 }
@@ -1,25 +1,41 @@
 use regex::Regex;
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
-pub fn determine_decision(review_text: &str) -> DecisionResult {
+use crate::config::{DecisionThresholds, ReviewCriterion, SummaryStrategy};
+
+const SUMMARY_MAX_CHARS: usize = 300;
+
+pub fn determine_decision(review_text: &str, strategy: SummaryStrategy) -> DecisionResult {
     let review_upper = review_text.to_uppercase();
     let review_lower = review_text.to_lowercase();
 
-    let (decision, is_accepted) = if Regex::new(r"FINAL DECISION:\s*\*\*ACCEPTED\*\*")
+    let minor_revision = Regex::new(r"FINAL DECISION:\s*\*\*ACCEPTED WITH MINOR REVISION")
+        .unwrap()
+        .is_match(&review_upper);
+
+    let marker = if Regex::new(r"FINAL DECISION:\s*\*\*ACCEPTED\*\*")
         .unwrap()
         .is_match(&review_upper)
     {
-        ("ACCEPTED".to_string(), true)
+        Some(("ACCEPTED".to_string(), true))
     } else if Regex::new(r"FINAL DECISION:\s*\*\*ACCEPTED WITH (MINOR|MAJOR) REVISION")
         .unwrap()
         .is_match(&review_upper)
     {
-        ("REVISION".to_string(), false)
+        Some(("REVISION".to_string(), false))
     } else if Regex::new(r"FINAL DECISION:\s*\*\*REJECTED\*\*")
         .unwrap()
         .is_match(&review_upper)
     {
-        ("REJECTED".to_string(), false)
+        Some(("REJECTED".to_string(), false))
+    } else {
+        None
+    };
+    let ambiguous = marker.is_none();
+
+    // Without a marker, fall back to keyword heuristics
+    let (decision, is_accepted) = if let Some(marker) = marker {
+        marker
     } else if (review_lower.contains("accepted") && !review_lower.contains("rejected"))
         || review_lower.contains("recommend publication")
     {
@@ -35,28 +51,20 @@ pub fn determine_decision(review_text: &str) -> DecisionResult {
         ("REVISION".to_string(), false)
     };
 
-    let summary = review_text
-        .split("\n\n")
-        .next()
-        .unwrap_or(review_text)
-        .to_string();
-    let truncated_summary = if summary.len() > 300 {
-        format!("{}...", &summary[..300])
-    } else {
-        summary
-    };
-
-    let full_review = if review_text.len() > 1000 {
-        format!("{}...", &review_text[..1000])
-    } else {
-        review_text.to_string()
+    let summary = match strategy {
+        SummaryStrategy::FirstParagraph => first_paragraph(review_text),
+        SummaryStrategy::Strengths | SummaryStrategy::Model => strengths_section(review_text)
+            .unwrap_or_else(|| first_substantive_paragraph(review_text)),
     };
+    let truncated_summary = truncate_summary(&summary);
 
     DecisionResult {
         decision,
         summary: truncated_summary,
-        full_review,
+        full_review: review_text.to_string(),
         accepted: is_accepted,
+        minor_revision: minor_revision && !is_accepted,
+        ambiguous,
     }
 }
 
@@ -66,4 +74,208 @@ pub struct DecisionResult {
     pub summary: String,
     pub full_review: String,
     pub accepted: bool,
+    /// The reviewer asked for minor revisions (decision is REVISION).
+    pub minor_revision: bool,
+    /// No FINAL DECISION marker was found; the decision is a heuristic guess.
+    pub ambiguous: bool,
+}
+
+fn first_paragraph(review_text: &str) -> String {
+    review_text
+        .split("\n\n")
+        .next()
+        .unwrap_or(review_text)
+        .to_string()
+}
+
+/// First paragraph other than the fixed opening sentence the prompt asks for.
+fn first_substantive_paragraph(review_text: &str) -> String {
+    review_text
+        .split("\n\n")
+        .map(str::trim)
+        .find(|p| !p.is_empty() && !p.starts_with("The reviewer has evaluated"))
+        .map(str::to_string)
+        .unwrap_or_else(|| first_paragraph(review_text))
+}
+
+/// Text of the "major strengths" section: anything after the heading on its
+/// own line, then the paragraph that follows it.
+fn strengths_section(review_text: &str) -> Option<String> {
+    let heading =
+        Regex::new(r"(?im)^[#*\d.\s]*(summary of )?(major )?strengths\b[^:\n]*:?\**[ \t]*(.*)$")
+            .unwrap();
+    let caps = heading.captures(review_text)?;
+    let inline = caps.get(3).map(|m| m.as_str().trim()).unwrap_or("");
+    let rest = &review_text[caps.get(0)?.end()..];
+    let following = rest
+        .trim_start_matches(['\r', '\n'])
+        .split("\n\n")
+        .next()
+        .unwrap_or("")
+        .trim();
+
+    let section = if inline.is_empty() {
+        following.to_string()
+    } else if following.is_empty() || rest.starts_with("\n\n") {
+        inline.to_string()
+    } else {
+        format!("{} {}", inline, following)
+    };
+    // Bullet lists read as a semicolon-separated sentence
+    let bulleted = section
+        .lines()
+        .any(|l| l.trim_start().starts_with(['-', '•']));
+    let cleaned = section
+        .lines()
+        .map(|l| l.trim().trim_start_matches(['-', '*', '•']).trim())
+        .filter(|l| !l.is_empty())
+        .collect::<Vec<_>>()
+        .join(if bulleted { "; " } else { " " })
+        .replace("**", "");
+    (!cleaned.is_empty()).then_some(cleaned)
+}
+
+/// Longest single reason kept from a weaknesses section.
+const REASON_MAX_CHARS: usize = 200;
+
+/// Up to `max` key reasons behind a non-accepting decision, taken from the
+/// review's weaknesses section (one per bullet, or its first paragraph).
+/// Reviews without a recognisable section yield `fallback_summary`.
+pub fn decision_reasons(review_text: &str, fallback_summary: &str, max: usize) -> Vec<String> {
+    if max == 0 {
+        return Vec::new();
+    }
+    let mut reasons = weaknesses_section(review_text);
+    if reasons.is_empty() && !fallback_summary.trim().is_empty() {
+        reasons.push(fallback_summary.trim().to_string());
+    }
+    reasons.truncate(max);
+    reasons
+        .into_iter()
+        .map(|r| match r.char_indices().nth(REASON_MAX_CHARS) {
+            Some((end, _)) => format!("{}...", &r[..end]),
+            None => r,
+        })
+        .collect()
+}
+
+/// Items under the first weaknesses/limitations/concerns heading: one per
+/// bullet, or one per sentence of a prose paragraph. The section ends at the
+/// next heading, or at the blank line after a paragraph.
+fn weaknesses_section(review_text: &str) -> Vec<String> {
+    let heading = Regex::new(
+        r"(?im)^[#*\d.\s]*(minor |major |main |key )?(weaknesses|limitations|concerns|areas for improvement)\b[^:\n]*:?\**[ \t]*(.*)$",
+    )
+    .unwrap();
+    let Some(caps) = heading.captures(review_text) else {
+        return Vec::new();
+    };
+    let next_heading =
+        Regex::new(r"^(#+\s|\*\*[^*]+\*\*:?$|[A-Z][^.]{0,60}:$|\d+\.\s+\*\*)").unwrap();
+    let clean = |line: &str| {
+        line.trim()
+            .trim_start_matches(['-', '*', '•'])
+            .trim()
+            .replace("**", "")
+    };
+
+    let mut bullets: Vec<String> = Vec::new();
+    let mut paragraph = caps.get(3).map(|m| clean(m.as_str())).unwrap_or_default();
+    let rest = &review_text[caps.get(0).map(|m| m.end()).unwrap_or(0)..];
+    for line in rest.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            if !paragraph.is_empty() && bullets.is_empty() {
+                break;
+            }
+            continue;
+        }
+        if next_heading.is_match(trimmed) {
+            break;
+        }
+        if trimmed.starts_with(['-', '*', '•']) {
+            bullets.push(clean(trimmed));
+        } else if let Some(last) = bullets.last_mut() {
+            // Indented lines continue a bullet; anything else ends the list
+            if !line.starts_with(char::is_whitespace) {
+                break;
+            }
+            last.push(' ');
+            last.push_str(trimmed);
+        } else {
+            if !paragraph.is_empty() {
+                paragraph.push(' ');
+            }
+            paragraph.push_str(&clean(trimmed));
+        }
+    }
+
+    if !bullets.is_empty() {
+        bullets.retain(|b| !b.is_empty());
+        return bullets;
+    }
+    paragraph
+        .split_inclusive(". ")
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn truncate_summary(summary: &str) -> String {
+    match summary.char_indices().nth(SUMMARY_MAX_CHARS) {
+        Some((end, _)) => format!("{}...", &summary[..end]),
+        None => summary.to_string(),
+    }
+}
+
+/// Where a review's parsed scores fall relative to the decision thresholds.
+#[derive(Debug, Clone, Copy)]
+pub struct DecisionConfidence {
+    /// Criterion scores weighted as in `REVIEW_CRITERIA`, 0-100.
+    pub weighted_score: f64,
+    /// 0 on a threshold, rising to 1 at twice `margin` from the nearest one.
+    pub confidence: f64,
+}
+
+/// Confidence below this is reported as low: the weighted score is within
+/// `DECISION_CONFIDENCE_MARGIN` of a threshold.
+pub const LOW_CONFIDENCE: f64 = 0.5;
+
+/// Computes the weighted score from the structured `scores` and how far it
+/// lies from the nearest threshold. Criteria the review did not score are
+/// left out of the weighting; `None` when it scored none of them.
+pub fn decision_confidence(
+    scores: &BTreeMap<String, f64>,
+    criteria: &[ReviewCriterion],
+    thresholds: &DecisionThresholds,
+    margin: f64,
+) -> Option<DecisionConfidence> {
+    let (total, weights) = criteria
+        .iter()
+        .filter_map(|c| {
+            scores
+                .iter()
+                .find(|(name, _)| name.trim().eq_ignore_ascii_case(&c.name))
+                .map(|(_, score)| (score.clamp(0.0, 100.0) * c.weight as f64, c.weight as f64))
+        })
+        .fold((0.0, 0.0), |(t, w), (score, weight)| {
+            (t + score, w + weight)
+        });
+    if weights <= 0.0 {
+        return None;
+    }
+    let weighted_score = total / weights;
+    let distance = [
+        thresholds.accept,
+        thresholds.minor_revision,
+        thresholds.major_revision,
+    ]
+    .iter()
+    .map(|t| (weighted_score - t).abs())
+    .fold(f64::INFINITY, f64::min);
+    Some(DecisionConfidence {
+        weighted_score,
+        confidence: (distance / (2.0 * margin)).min(1.0),
+    })
 }
@@ -0,0 +1,55 @@
+use thiserror::Error;
+
+/// Failures from paper extraction and the Claude API. Displayed text is what
+/// gets stored in ERROR review rows and returned to uploaders.
+#[derive(Debug, Error)]
+pub enum AgentError {
+    #[error("Request failed: {0}")]
+    Request(String),
+    #[error("Request timed out: {0}")]
+    Timeout(String),
+    #[error("AI service rejected the API key: {0}")]
+    Auth(String),
+    #[error("AI service rate limit reached on every model: {0}")]
+    RateLimited(String),
+    #[error("AI service error ({status}): {message}")]
+    Api { status: u16, message: String },
+    #[error("AI response stream interrupted: {0}")]
+    Stream(String),
+    #[error("No text in response")]
+    EmptyResponse,
+    #[error("This PDF is password-protected; please upload an unprotected copy.")]
+    EncryptedPdf,
+    #[error("PDF extraction error: {0}")]
+    Extraction(String),
+    #[error("PDF appears empty or has insufficient text ({0} chars)")]
+    InsufficientText(usize),
+}
+
+impl AgentError {
+    /// Whether running the same request again may succeed.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            AgentError::Request(_)
+            | AgentError::Timeout(_)
+            | AgentError::RateLimited(_)
+            | AgentError::Stream(_)
+            | AgentError::EmptyResponse => true,
+            AgentError::Api { status, .. } => *status >= 500,
+            AgentError::Auth(_)
+            | AgentError::EncryptedPdf
+            | AgentError::Extraction(_)
+            | AgentError::InsufficientText(_) => false,
+        }
+    }
+}
+
+impl From<reqwest::Error> for AgentError {
+    fn from(e: reqwest::Error) -> Self {
+        if e.is_timeout() {
+            AgentError::Timeout(e.to_string())
+        } else {
+            AgentError::Request(e.to_string())
+        }
+    }
+}
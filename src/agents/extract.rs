@@ -0,0 +1,187 @@
+use pdf_extract::OutputError;
+use std::io::Write;
+use std::path::Path;
+use std::time::Duration;
+
+use super::error::AgentError;
+
+/// Longest a `pdftotext` run may take before it is abandoned.
+const PDFTOTEXT_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Text extracted from an uploaded PDF.
+pub struct ExtractedText {
+    pub text: String,
+    /// Pages in the document, extracted or not.
+    pub page_count: usize,
+    /// Total page count when only the first `max_pages` were extracted.
+    pub truncated_from: Option<usize>,
+    /// [`extraction_quality`] of `text`.
+    pub quality: f64,
+}
+
+/// Extracts the text of an uploaded PDF, limited to the first `max_pages`
+/// pages when set. Fails when the document is password-protected or has too
+/// little text to review.
+pub fn extract_text_from_pdf(
+    data: &[u8],
+    max_pages: Option<usize>,
+) -> Result<ExtractedText, AgentError> {
+    // Documents with only an owner password decrypt with the empty user
+    // password inside pdf_extract; a decryption error means a real password
+    let map_err = |e| match e {
+        OutputError::PdfError(pdf_extract::Error::Decryption(_)) => AgentError::EncryptedPdf,
+        e => AgentError::Extraction(e.to_string()),
+    };
+
+    let pages = pdf_extract::extract_text_from_mem_by_pages(data).map_err(map_err)?;
+    let page_count = pages.len();
+    let truncated_from = max_pages
+        .filter(|max_pages| page_count > *max_pages)
+        .map(|_| page_count);
+    let mut text = pages
+        .into_iter()
+        .take(max_pages.unwrap_or(usize::MAX))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    if text.trim().len() < 100 {
+        return Err(AgentError::InsufficientText(text.len()));
+    }
+
+    let quality = extraction_quality(&text);
+    if let (Some(total), Some(max_pages)) = (truncated_from, max_pages) {
+        text.push_str(&truncation_note(max_pages, total));
+    }
+
+    Ok(ExtractedText {
+        text,
+        page_count,
+        truncated_from,
+        quality,
+    })
+}
+
+fn truncation_note(max_pages: usize, total: usize) -> String {
+    format!(
+        "\n\n[Note: text truncated; only the first {} of {} pages were extracted.]",
+        max_pages, total
+    )
+}
+
+/// Share (0.0-1.0) of the text's non-space characters that sit in word-like
+/// tokens: at most 25 characters, mostly letters. Clean prose scores well
+/// above 0.7; ligature soup and text run together without spaces score low.
+pub fn extraction_quality(text: &str) -> f64 {
+    let mut total = 0usize;
+    let mut wordlike = 0usize;
+    for token in text.split_whitespace() {
+        let chars = token.chars().count();
+        total += chars;
+        let word = token.trim_matches(|c: char| !c.is_alphanumeric());
+        let letters = word.chars().filter(|c| c.is_alphabetic()).count();
+        let len = word.chars().count();
+        if len > 0 && len <= 25 && letters * 5 >= len * 4 {
+            wordlike += chars;
+        }
+    }
+    if total == 0 {
+        return 0.0;
+    }
+    wordlike as f64 / total as f64
+}
+
+/// Re-extracts a paper whose text scored below `threshold` with poppler's
+/// `pdftotext`, keeping whichever text scores better. The PDF is written to
+/// a temp file in `temp_folder` for the run.
+pub async fn reextract_if_garbled(
+    extracted: ExtractedText,
+    data: &[u8],
+    pdftotext: &str,
+    temp_folder: &Path,
+    max_pages: Option<usize>,
+    threshold: f64,
+) -> ExtractedText {
+    if extracted.quality >= threshold {
+        return extracted;
+    }
+    let text = match run_pdftotext(data, pdftotext, temp_folder, max_pages).await {
+        Ok(text) => text,
+        Err(e) => {
+            tracing::warn!("Alternate extraction with {} failed: {}", pdftotext, e);
+            return extracted;
+        }
+    };
+    let quality = extraction_quality(&text);
+    tracing::info!(
+        "Extraction quality {:.2} with pdf_extract, {:.2} with {}",
+        extracted.quality,
+        quality,
+        pdftotext
+    );
+    if quality <= extracted.quality || text.trim().len() < 100 {
+        return extracted;
+    }
+    let mut text = text;
+    if let (Some(total), Some(max_pages)) = (extracted.truncated_from, max_pages) {
+        text.push_str(&truncation_note(max_pages, total));
+    }
+    ExtractedText {
+        text,
+        page_count: extracted.page_count,
+        truncated_from: extracted.truncated_from,
+        quality,
+    }
+}
+
+async fn run_pdftotext(
+    data: &[u8],
+    pdftotext: &str,
+    temp_folder: &Path,
+    max_pages: Option<usize>,
+) -> Result<String, String> {
+    let mut file = tempfile::Builder::new()
+        .suffix(".pdf")
+        .tempfile_in(temp_folder)
+        .map_err(|e| e.to_string())?;
+    file.write_all(data).map_err(|e| e.to_string())?;
+
+    let mut command = tokio::process::Command::new(pdftotext);
+    command.args(["-enc", "UTF-8"]);
+    if let Some(max_pages) = max_pages {
+        command.arg("-l").arg(max_pages.to_string());
+    }
+    let output = command
+        .arg(file.path())
+        .arg("-")
+        .kill_on_drop(true)
+        .output();
+    let output = tokio::time::timeout(PDFTOTEXT_TIMEOUT, output)
+        .await
+        .map_err(|_| "timed out".to_string())?
+        .map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err(format!(
+            "exited with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn password_protected_pdf_is_reported_as_encrypted() {
+        let err = extract_text_from_pdf(&crate::test_support::encrypted_pdf(), None)
+            .err()
+            .expect("extraction fails");
+        assert!(matches!(err, AgentError::EncryptedPdf), "got {:?}", err);
+        assert_eq!(
+            err.to_string(),
+            "This PDF is password-protected; please upload an unprotected copy."
+        );
+    }
+}
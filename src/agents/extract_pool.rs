@@ -0,0 +1,54 @@
+// Dedicated threads for PDF parsing. Extraction is CPU-bound and can take
+// seconds per paper; running it on its own fixed pool keeps batch ingestion
+// from filling tokio's blocking pool and bounds the CPU it uses.
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::{mpsc, Arc, Mutex};
+use tokio::sync::oneshot;
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// `EXTRACTION_WORKERS` threads running extraction jobs in arrival order;
+/// jobs beyond that wait in the queue.
+#[derive(Clone)]
+pub struct ExtractionPool {
+    jobs: mpsc::Sender<Job>,
+}
+
+impl ExtractionPool {
+    pub fn new(workers: usize) -> Self {
+        let (jobs, queue) = mpsc::channel::<Job>();
+        let queue = Arc::new(Mutex::new(queue));
+        for i in 0..workers.max(1) {
+            let queue = queue.clone();
+            std::thread::Builder::new()
+                .name(format!("pdf-extract-{}", i))
+                .spawn(move || loop {
+                    // The lock is only held while waiting for the next job
+                    let job = match queue.lock().unwrap_or_else(|e| e.into_inner()).recv() {
+                        Ok(job) => job,
+                        Err(_) => return,
+                    };
+                    // A panicking document fails its own job, not the worker
+                    let _ = catch_unwind(AssertUnwindSafe(job));
+                })
+                .expect("failed to spawn PDF extraction worker");
+        }
+        Self { jobs }
+    }
+
+    /// Runs `f` on a pool thread once one is free. Fails only when the job
+    /// panicked.
+    pub async fn run<T, F>(&self, f: F) -> Result<T, String>
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        let (tx, rx) = oneshot::channel();
+        self.jobs
+            .send(Box::new(move || {
+                let _ = tx.send(f());
+            }))
+            .map_err(|_| "PDF extraction pool stopped".to_string())?;
+        rx.await.map_err(|_| "PDF extraction panicked".to_string())
+    }
+}
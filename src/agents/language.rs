@@ -0,0 +1,90 @@
+// Lightweight language identification by stopword frequency.
+// Good enough to tell the common European languages apart on a full paper;
+// anything it cannot place confidently is reported as unknown.
+
+const STOPWORDS: &[(&str, &[&str])] = &[
+    (
+        "en",
+        &[
+            "the", "and", "of", "to", "in", "is", "that", "for", "with", "as", "are", "this", "on",
+            "by", "be", "we", "which", "from", "it", "an",
+        ],
+    ),
+    (
+        "es",
+        &[
+            "el", "la", "de", "que", "y", "en", "los", "del", "se", "las", "por", "un", "para",
+            "con", "una", "es", "al", "lo", "como", "más",
+        ],
+    ),
+    (
+        "fr",
+        &[
+            "le", "la", "les", "de", "des", "et", "en", "un", "une", "du", "est", "que", "pour",
+            "dans", "qui", "par", "sur", "au", "avec", "ce",
+        ],
+    ),
+    (
+        "de",
+        &[
+            "der", "die", "und", "das", "den", "zu", "ist", "von", "mit", "des", "sich", "nicht",
+            "auf", "ein", "eine", "für", "im", "dem", "werden", "auch",
+        ],
+    ),
+    (
+        "it",
+        &[
+            "il", "di", "che", "la", "e", "per", "un", "una", "del", "della", "sono", "con", "non",
+            "gli", "le", "da", "nel", "dei", "delle", "come",
+        ],
+    ),
+    (
+        "pt",
+        &[
+            "de", "que", "o", "a", "do", "da", "em", "um", "para", "com", "não", "uma", "os", "no",
+            "se", "na", "por", "mais", "as", "dos",
+        ],
+    ),
+    (
+        "nl",
+        &[
+            "de", "het", "een", "van", "en", "in", "is", "dat", "op", "te", "zijn", "voor", "met",
+            "die", "niet", "aan", "er", "ook", "als", "door",
+        ],
+    ),
+];
+
+/// Only the start of the text is sampled; a few thousand words are plenty.
+const SAMPLE_WORDS: usize = 5000;
+/// Minimum share of sampled words that must be stopwords of the winner.
+const MIN_STOPWORD_RATIO: f64 = 0.08;
+
+/// Returns the ISO 639-1 code of the most likely language, or `None` when
+/// the text does not look like any supported language.
+pub fn detect_language(text: &str) -> Option<&'static str> {
+    let words: Vec<String> = text
+        .split(|c: char| !c.is_alphabetic())
+        .filter(|w| !w.is_empty())
+        .take(SAMPLE_WORDS)
+        .map(str::to_lowercase)
+        .collect();
+    if words.is_empty() {
+        return None;
+    }
+
+    let (code, hits) = STOPWORDS
+        .iter()
+        .map(|(code, stopwords)| {
+            let hits = words
+                .iter()
+                .filter(|w| stopwords.contains(&w.as_str()))
+                .count();
+            (*code, hits)
+        })
+        .max_by_key(|(_, hits)| *hits)?;
+
+    if (hits as f64) / (words.len() as f64) < MIN_STOPWORD_RATIO {
+        return None;
+    }
+    Some(code)
+}
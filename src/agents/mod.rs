@@ -1,5 +1,25 @@
+mod active_content;
+mod batch;
 mod claude;
 mod decision;
+mod error;
+mod extract;
+mod extract_pool;
+mod injection;
+mod language;
+mod preprocess;
+mod similarity;
+mod structured;
 
+pub use active_content::*;
+pub use batch::*;
 pub use claude::*;
-pub use decision::*;
+pub use decision::{decision_confidence, decision_reasons, LOW_CONFIDENCE};
+pub use error::*;
+pub use extract::*;
+pub use extract_pool::ExtractionPool;
+pub use injection::*;
+pub use language::*;
+pub use preprocess::Preprocessing;
+pub use similarity::*;
+pub use structured::Review;
@@ -0,0 +1,87 @@
+// Optional cleanup of extracted paper text before it is sent for review:
+// running headers/footers, page numbers and the reference list add tokens
+// without helping the reviewer.
+use regex::Regex;
+use std::collections::HashMap;
+
+/// Which cleanup steps run on paper text before review.
+#[derive(Clone, Copy, Default)]
+pub struct Preprocessing {
+    /// Drop page-number lines and lines repeated on many pages.
+    pub strip_boilerplate: bool,
+    /// Drop the references section, keeping any appendix after it.
+    pub trim_references: bool,
+}
+
+/// A line seen this many times (digits ignored) is a running header/footer.
+const REPEATED_LINE_MIN: usize = 3;
+
+impl Preprocessing {
+    pub fn enabled(&self) -> bool {
+        self.strip_boilerplate || self.trim_references
+    }
+
+    /// Applies the enabled steps; the text is returned unchanged when none are.
+    pub fn apply(&self, text: &str) -> String {
+        if !self.enabled() {
+            return text.to_string();
+        }
+        let mut lines: Vec<&str> = text.lines().collect();
+        if self.strip_boilerplate {
+            lines = strip_boilerplate(lines);
+        }
+        if self.trim_references {
+            lines = trim_references(lines);
+        }
+        lines.join("\n")
+    }
+}
+
+/// Header/footer lines differ from page to page only in their numbers.
+fn normalized(line: &str) -> String {
+    line.trim()
+        .chars()
+        .map(|c| if c.is_ascii_digit() { '#' } else { c })
+        .collect()
+}
+
+fn strip_boilerplate(lines: Vec<&str>) -> Vec<&str> {
+    let page_number = Regex::new(r"(?i)^(page\s*)?\d{1,4}(\s*(of|/)\s*\d{1,4})?$").unwrap();
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for line in &lines {
+        let key = normalized(line);
+        if (10..=100).contains(&key.chars().count()) && key.chars().any(char::is_alphabetic) {
+            *counts.entry(key).or_default() += 1;
+        }
+    }
+    lines
+        .into_iter()
+        .filter(|line| !page_number.is_match(line.trim()))
+        .filter(|line| {
+            counts
+                .get(&normalized(line))
+                .is_none_or(|n| *n < REPEATED_LINE_MIN)
+        })
+        .collect()
+}
+
+fn trim_references(lines: Vec<&str>) -> Vec<&str> {
+    let heading =
+        Regex::new(r"(?i)^(\d+\.?\s*|[ivx]+\.\s*)?(references|bibliography|works cited)$").unwrap();
+    let resume = Regex::new(r"(?i)^(appendix|appendices|supplementary material|\[note:)").unwrap();
+    // The last heading in the second half; earlier ones are usually a table of contents
+    let Some(start) = lines
+        .iter()
+        .rposition(|line| heading.is_match(line.trim()))
+        .filter(|i| *i >= lines.len() / 2)
+    else {
+        return lines;
+    };
+    let end = lines[start + 1..]
+        .iter()
+        .position(|line| resume.is_match(line.trim()))
+        .map_or(lines.len(), |i| start + 1 + i);
+    let mut kept = lines[..start].to_vec();
+    kept.extend_from_slice(&lines[end..]);
+    kept
+}
@@ -0,0 +1,33 @@
+// How much the reviews of one submission say the same thing.
+use std::collections::HashSet;
+
+/// Lowercased alphanumeric words of three or more characters.
+fn token_set(text: &str) -> HashSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| w.chars().count() >= 3)
+        .map(|w| w.to_lowercase())
+        .collect()
+}
+
+fn jaccard(a: &HashSet<String>, b: &HashSet<String>) -> f64 {
+    let union = a.union(b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    a.intersection(b).count() as f64 / union as f64
+}
+
+/// Mean pairwise Jaccard similarity of the reviews' word sets, from 0.0
+/// (nothing shared) to 1.0 (same vocabulary); `None` with fewer than two.
+pub fn review_similarity(reviews: &[&str]) -> Option<f64> {
+    let sets: Vec<HashSet<String>> = reviews.iter().map(|r| token_set(r)).collect();
+    let mut total = 0.0;
+    let mut pairs = 0;
+    for i in 0..sets.len() {
+        for j in i + 1..sets.len() {
+            total += jaccard(&sets[i], &sets[j]);
+            pairs += 1;
+        }
+    }
+    (pairs > 0).then(|| total / pairs as f64)
+}
@@ -0,0 +1,81 @@
+// Machine-readable review output requested alongside the prose review.
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use super::decision::{determine_decision, DecisionResult};
+use crate::config::SummaryStrategy;
+
+/// Appended to the system prompt so each review ends with a JSON summary.
+pub const STRUCTURED_OUTPUT_INSTRUCTIONS: &str = "After the FINAL DECISION line, append a fenced \
+```json block containing a single JSON object with these fields: \"scores\" (an object mapping \
+each criterion name to its 0-100 score), \"decision\" (one of \"ACCEPTED\", \"ACCEPTED WITH MINOR \
+REVISION REQUIRED\", \"ACCEPTED WITH MAJOR REVISION REQUIRED\", \"REJECTED\"), \"summary\" (two or \
+three sentences) and \"suggestions\" (an array of short strings). The JSON must agree with the \
+review above it.";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StructuredReview {
+    #[serde(default)]
+    pub scores: BTreeMap<String, f64>,
+    pub decision: String,
+    #[serde(default)]
+    pub summary: String,
+    #[serde(default)]
+    pub suggestions: Vec<String>,
+}
+
+/// A generated review: the prose shown to users and, when the model
+/// complied, the parsed JSON object.
+#[derive(Debug, Clone)]
+pub struct Review {
+    pub text: String,
+    pub structured: Option<StructuredReview>,
+}
+
+impl Review {
+    /// Splits the trailing ```json block off the raw model output. Output
+    /// without a parseable block is kept whole as text.
+    pub fn from_model_output(raw: &str) -> Self {
+        let block = Regex::new(r"(?s)```json\s*(\{.*\})\s*```\s*$").unwrap();
+        if let Some(caps) = block.captures(raw.trim_end()) {
+            if let Ok(structured) = serde_json::from_str::<StructuredReview>(&caps[1]) {
+                let start = caps.get(0).map(|m| m.start()).unwrap_or(raw.len());
+                return Self {
+                    text: raw[..start].trim_end().to_string(),
+                    structured: Some(structured),
+                };
+            }
+        }
+        Self {
+            text: raw.to_string(),
+            structured: None,
+        }
+    }
+
+    /// Uses the structured decision when it names a known outcome and
+    /// otherwise parses the prose. The structured summary is used only under
+    /// `SummaryStrategy::Model`.
+    pub fn decision(&self, summary: SummaryStrategy) -> DecisionResult {
+        let mut result = determine_decision(&self.text, summary);
+        let Some(structured) = &self.structured else {
+            return result;
+        };
+        let (decision, accepted, minor_revision) =
+            match structured.decision.trim().to_uppercase().as_str() {
+                "ACCEPTED" => ("ACCEPTED", true, false),
+                "ACCEPTED WITH MINOR REVISION REQUIRED" => ("REVISION", false, true),
+                "ACCEPTED WITH MAJOR REVISION REQUIRED" => ("REVISION", false, false),
+                "REJECTED" => ("REJECTED", false, false),
+                _ => return result,
+            };
+        result.decision = decision.to_string();
+        result.accepted = accepted;
+        result.minor_revision = minor_revision;
+        result.ambiguous = false;
+        if summary == SummaryStrategy::Model && !structured.summary.trim().is_empty() {
+            result.summary = structured.summary.trim().to_string();
+        }
+        result
+    }
+}
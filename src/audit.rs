@@ -0,0 +1,55 @@
+// Audit trail of mutating operations (AUDIT_LOG): who did what to which
+// submission, and whether it worked. Rows are append-only in the database.
+use axum::http::HeaderMap;
+use std::net::SocketAddr;
+
+use crate::state::AppState;
+
+pub const SUCCESS: &str = "success";
+pub const FAILURE: &str = "failure";
+/// Actor for operations the server starts itself, such as the retention sweep.
+pub const SYSTEM: &str = "system";
+
+/// Who made the request: the client address, or with `TRUST_FORWARDED_FOR`
+/// the first `X-Forwarded-For` entry set by the reverse proxy.
+pub fn actor(state: &AppState, headers: &HeaderMap, addr: SocketAddr) -> String {
+    let forwarded = state
+        .config
+        .trust_forwarded_for
+        .then(|| headers.get("X-Forwarded-For")?.to_str().ok())
+        .flatten()
+        .and_then(|v| v.split(',').next())
+        .map(str::trim)
+        .filter(|v| !v.is_empty());
+    match forwarded {
+        Some(client) => client.to_string(),
+        None => addr.ip().to_string(),
+    }
+}
+
+/// Records one operation when `AUDIT_LOG` is on. A failed write is logged
+/// but never fails the operation itself.
+pub async fn record(
+    state: &AppState,
+    action: &str,
+    submission_id: Option<&str>,
+    actor: &str,
+    outcome: &str,
+    detail: Option<&str>,
+) {
+    if !state.config.audit_log {
+        return;
+    }
+    if let Err(e) = crate::db::insert_audit_entry(
+        state.pool.as_ref(),
+        action,
+        submission_id,
+        actor,
+        outcome,
+        detail,
+    )
+    .await
+    {
+        tracing::error!("Failed to write audit entry for {}: {}", action, e);
+    }
+}
@@ -2,42 +2,125 @@ mod models;
 
 pub use models::*;
 
-use sqlx::postgres::PgPoolOptions;
+use sqlx::postgres::{PgConnectOptions, PgPoolOptions};
 use sqlx::PgPool;
+use std::str::FromStr;
 use std::sync::Arc;
 
 pub type DbPool = Arc<PgPool>;
 
-pub async fn create_pool(database_url: &str) -> Result<DbPool, sqlx::Error> {
+/// Connects with `statement_timeout` set on every session so a hung query
+/// cannot hold a pool connection indefinitely; 0 leaves it unbounded.
+pub async fn create_pool(
+    database_url: &str,
+    statement_timeout_ms: u64,
+) -> Result<DbPool, sqlx::Error> {
+    let options = PgConnectOptions::from_str(database_url)?
+        .options([("statement_timeout", statement_timeout_ms.to_string())]);
     let pool = PgPoolOptions::new()
         .max_connections(5)
-        .connect(database_url)
+        .connect_with(options)
         .await?;
 
     Ok(Arc::new(pool))
 }
 
+/// Attempts made by [`with_retry`] before giving up.
+const WRITE_ATTEMPTS: u32 = 4;
+
+/// Errors a retry may clear: a dropped or refused connection, an exhausted
+/// pool, or the server shutting down or failing over.
+pub fn is_transient(error: &sqlx::Error) -> bool {
+    match error {
+        sqlx::Error::Io(_) | sqlx::Error::PoolTimedOut => true,
+        sqlx::Error::Database(db) => db
+            .code()
+            .is_some_and(|code| code.starts_with("08") || code.starts_with("57P")),
+        _ => false,
+    }
+}
+
+/// Runs a critical write, retrying transient failures with exponential
+/// backoff. Read paths should fail fast instead of using this.
+pub async fn with_retry<T, F, Fut>(what: &str, mut op: F) -> Result<T, sqlx::Error>
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = Result<T, sqlx::Error>>,
+{
+    let mut delay = std::time::Duration::from_millis(250);
+    let mut attempt = 1;
+    loop {
+        match op().await {
+            Err(e) if attempt < WRITE_ATTEMPTS && is_transient(&e) => {
+                tracing::warn!(
+                    "{} failed ({}); retrying in {:?} ({}/{})",
+                    what,
+                    e,
+                    delay,
+                    attempt,
+                    WRITE_ATTEMPTS - 1
+                );
+                tokio::time::sleep(delay).await;
+                delay *= 2;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
 pub async fn run_migrations(pool: &PgPool) -> Result<(), sqlx::Error> {
-    sqlx::migrate!("./migrations").run(pool).await
+    sqlx::migrate!("./migrations").run(pool).await?;
+    Ok(())
+}
+
+pub async fn create_submission(pool: &PgPool, new: &NewSubmission<'_>) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO submissions (submission_id, paper_title, filename, file_path, processing_complete, all_accepted,
+                                 category, detected_language, flags, content_type, author_token_hash,
+                                 parent_submission_id, expires_at, extraction_quality, page_count, tenant_id,
+                                 active_content)
+        VALUES ($1, $2, $3, $4, false, false, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)
+        "#,
+    )
+    .bind(new.submission_id)
+    .bind(new.paper_title)
+    .bind(new.filename)
+    .bind(new.file_path)
+    .bind(new.category)
+    .bind(new.detected_language)
+    .bind(new.flags)
+    .bind(new.content_type)
+    .bind(new.author_token_hash)
+    .bind(new.parent_submission_id)
+    .bind(new.expires_at)
+    .bind(new.extraction_quality)
+    .bind(new.page_count)
+    .bind(new.tenant_id)
+    .bind(new.active_content)
+    .execute(pool)
+    .await?;
+    Ok(())
 }
 
-pub async fn create_submission(
+/// Adds one review's token usage to the submission's running totals.
+pub async fn add_submission_token_usage(
     pool: &PgPool,
     submission_id: &str,
-    paper_title: &str,
-    filename: &str,
-    file_path: &str,
+    input_tokens: u64,
+    output_tokens: u64,
 ) -> Result<(), sqlx::Error> {
     sqlx::query(
         r#"
-        INSERT INTO submissions (submission_id, paper_title, filename, file_path, processing_complete, all_accepted)
-        VALUES ($1, $2, $3, $4, false, false)
+        UPDATE submissions
+        SET input_tokens = input_tokens + $2, output_tokens = output_tokens + $3
+        WHERE submission_id = $1
         "#,
     )
     .bind(submission_id)
-    .bind(paper_title)
-    .bind(filename)
-    .bind(file_path)
+    .bind(input_tokens as i64)
+    .bind(output_tokens as i64)
     .execute(pool)
     .await?;
     Ok(())
@@ -47,20 +130,322 @@ pub async fn get_submission(
     pool: &PgPool,
     submission_id: &str,
 ) -> Result<Option<Submission>, sqlx::Error> {
+    sqlx::query_as::<_, Submission>("SELECT * FROM submissions WHERE submission_id = $1")
+        .bind(submission_id)
+        .fetch_optional(pool)
+        .await
+}
+
+pub async fn list_submissions(
+    pool: &PgPool,
+    category: Option<&str>,
+    tenant_id: Option<&str>,
+) -> Result<Vec<Submission>, sqlx::Error> {
     sqlx::query_as::<_, Submission>(
-        "SELECT * FROM submissions WHERE submission_id = $1",
+        r#"
+        SELECT * FROM submissions
+        WHERE ($1::TEXT IS NULL OR category = $1) AND ($2::TEXT IS NULL OR tenant_id = $2)
+        ORDER BY created_at DESC
+        "#,
+    )
+    .bind(category)
+    .bind(tenant_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// Progress of each listed submission that exists, in one query.
+pub async fn get_submission_progress(
+    pool: &PgPool,
+    submission_ids: &[String],
+) -> Result<Vec<SubmissionProgress>, sqlx::Error> {
+    sqlx::query_as::<_, SubmissionProgress>(
+        r#"
+        SELECT s.submission_id, s.processing_complete, s.all_accepted, s.certificate_filename,
+               s.certificate_kind, s.author_token_hash, s.expires_at, s.tenant_id,
+               (SELECT COUNT(*) FROM review_results r
+                WHERE r.submission_id = s.submission_id AND NOT r.superseded)
+                   AS completed_reviews,
+               EXISTS (SELECT 1 FROM review_jobs j
+                       WHERE j.submission_id = s.submission_id AND j.status = 'running')
+                   AS review_started
+        FROM submissions s
+        WHERE s.submission_id = ANY($1)
+        "#,
+    )
+    .bind(submission_ids)
+    .fetch_all(pool)
+    .await
+}
+
+/// Adds `flag` to the submission's flags unless it is already present.
+pub async fn add_submission_flag(
+    pool: &PgPool,
+    submission_id: &str,
+    flag: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE submissions SET flags = array_append(flags, $2)
+        WHERE submission_id = $1 AND NOT ($2 = ANY(flags))
+        "#,
+    )
+    .bind(submission_id)
+    .bind(flag)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Corrects a submission's title; reviews and certificate are left as they are.
+pub async fn update_paper_title(
+    pool: &PgPool,
+    submission_id: &str,
+    paper_title: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query("UPDATE submissions SET paper_title = $2 WHERE submission_id = $1")
+        .bind(submission_id)
+        .bind(paper_title)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// Removes a submission that was never queued for review.
+pub async fn delete_submission(pool: &PgPool, submission_id: &str) -> Result<(), sqlx::Error> {
+    sqlx::query("DELETE FROM submissions WHERE submission_id = $1")
+        .bind(submission_id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// Replaces a submission's author token hash, invalidating the old token.
+pub async fn rotate_author_token_hash(
+    pool: &PgPool,
+    submission_id: &str,
+    author_token_hash: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query("UPDATE submissions SET author_token_hash = $2 WHERE submission_id = $1")
+        .bind(submission_id)
+        .bind(author_token_hash)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// Returns the most recent submission whose title normalizes to `normalized`
+/// (lowercase, non-alphanumeric runs collapsed to single spaces).
+pub async fn find_submission_by_normalized_title(
+    pool: &PgPool,
+    normalized: &str,
+    tenant_id: Option<&str>,
+) -> Result<Option<String>, sqlx::Error> {
+    sqlx::query_scalar::<_, String>(
+        r#"
+        SELECT submission_id FROM submissions
+        WHERE btrim(regexp_replace(lower(paper_title), '[^[:alnum:]]+', ' ', 'g')) = $1
+          AND tenant_id IS NOT DISTINCT FROM $2
+        ORDER BY created_at DESC
+        LIMIT 1
+        "#,
+    )
+    .bind(normalized)
+    .bind(tenant_id)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Submissions this one revises, oldest first.
+pub async fn list_previous_versions(
+    pool: &PgPool,
+    submission_id: &str,
+) -> Result<Vec<String>, sqlx::Error> {
+    sqlx::query_scalar::<_, String>(
+        r#"
+        WITH RECURSIVE lineage(submission_id, parent_submission_id, depth) AS (
+            SELECT submission_id, parent_submission_id, 0 FROM submissions WHERE submission_id = $1
+            UNION ALL
+            SELECT s.submission_id, s.parent_submission_id, l.depth + 1
+            FROM submissions s JOIN lineage l ON s.submission_id = l.parent_submission_id
+        )
+        SELECT submission_id FROM lineage WHERE depth > 0 ORDER BY depth DESC
+        "#,
+    )
+    .bind(submission_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// Submissions that directly revise this one, oldest first.
+pub async fn list_revisions(
+    pool: &PgPool,
+    submission_id: &str,
+) -> Result<Vec<String>, sqlx::Error> {
+    sqlx::query_scalar::<_, String>(
+        "SELECT submission_id FROM submissions WHERE parent_submission_id = $1 ORDER BY created_at",
+    )
+    .bind(submission_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// Returns the submission `tenant_id` created under `key` within the last
+/// `ttl_secs`.
+pub async fn find_idempotent_submission(
+    pool: &PgPool,
+    tenant_id: Option<&str>,
+    key: &str,
+    ttl_secs: i64,
+) -> Result<Option<String>, sqlx::Error> {
+    sqlx::query_scalar::<_, String>(
+        r#"
+        SELECT submission_id FROM idempotency_keys
+        WHERE tenant_id = $1 AND idempotency_key = $2
+          AND created_at > NOW() - make_interval(secs => $3::DOUBLE PRECISION)
+        "#,
+    )
+    .bind(tenant_id.unwrap_or(""))
+    .bind(key)
+    .bind(ttl_secs)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Records `tenant_id`'s `key` for `submission_id`, replacing an expired
+/// entry and pruning other expired keys.
+pub async fn save_idempotency_key(
+    pool: &PgPool,
+    tenant_id: Option<&str>,
+    key: &str,
+    submission_id: &str,
+    ttl_secs: i64,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "DELETE FROM idempotency_keys WHERE created_at <= NOW() - make_interval(secs => $1::DOUBLE PRECISION)",
+    )
+    .bind(ttl_secs)
+    .execute(pool)
+    .await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO idempotency_keys (tenant_id, idempotency_key, submission_id)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (tenant_id, idempotency_key)
+        DO UPDATE SET submission_id = EXCLUDED.submission_id, created_at = NOW()
+        "#,
+    )
+    .bind(tenant_id.unwrap_or(""))
+    .bind(key)
+    .bind(submission_id)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+pub async fn count_review_results(pool: &PgPool, submission_id: &str) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM review_results WHERE submission_id = $1 AND NOT superseded",
+    )
+    .bind(submission_id)
+    .fetch_one(pool)
+    .await
+}
+
+/// Marks `reviewer_name`'s reviews stored before row `latest_id` as
+/// superseded, keeping them as history once a retry has stored a newer one.
+/// Row ids rather than timestamps order the reviews, so app/database clock
+/// skew cannot supersede the new row.
+pub async fn supersede_review_results(
+    pool: &PgPool,
+    submission_id: &str,
+    reviewer_name: &str,
+    latest_id: i32,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE review_results SET superseded = true
+        WHERE submission_id = $1 AND reviewer_name = $2 AND id < $3
+        "#,
+    )
+    .bind(submission_id)
+    .bind(reviewer_name)
+    .bind(latest_id)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Reviews for a submission in reviewer order, optionally only those with
+/// `decision`, one page at a time. Reviews replaced by a retry are left out
+/// unless `include_superseded`.
+pub async fn list_review_results(
+    pool: &PgPool,
+    submission_id: &str,
+    decision: Option<&str>,
+    include_superseded: bool,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<ReviewResult>, sqlx::Error> {
+    sqlx::query_as::<_, ReviewResult>(
+        r#"
+        SELECT * FROM review_results
+        WHERE submission_id = $1 AND ($2::TEXT IS NULL OR decision = $2)
+          AND ($3 OR NOT superseded)
+        ORDER BY reviewer_name, created_at
+        LIMIT $4 OFFSET $5
+        "#,
+    )
+    .bind(submission_id)
+    .bind(decision)
+    .bind(include_superseded)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(pool)
+    .await
+}
+
+/// The latest review by `reviewer_name` for a submission.
+pub async fn get_review_result(
+    pool: &PgPool,
+    submission_id: &str,
+    reviewer_name: &str,
+) -> Result<Option<ReviewResult>, sqlx::Error> {
+    sqlx::query_as::<_, ReviewResult>(
+        r#"
+        SELECT * FROM review_results
+        WHERE submission_id = $1 AND reviewer_name = $2 AND NOT superseded
+        ORDER BY created_at DESC
+        LIMIT 1
+        "#,
     )
     .bind(submission_id)
+    .bind(reviewer_name)
     .fetch_optional(pool)
     .await
 }
 
+/// Replaces one review's short summary.
+pub async fn update_review_summary(
+    pool: &PgPool,
+    id: i32,
+    summary: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query("UPDATE review_results SET summary = $2 WHERE id = $1")
+        .bind(id)
+        .bind(summary)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
 pub async fn get_review_results(
     pool: &PgPool,
     submission_id: &str,
-) -> Result<std::collections::HashMap<String, serde_json::Value>, sqlx::Error> {
+) -> Result<std::collections::HashMap<String, ReviewResultDisplay>, sqlx::Error> {
     let rows = sqlx::query_as::<_, ReviewResult>(
-        "SELECT * FROM review_results WHERE submission_id = $1 ORDER BY reviewer_name",
+        "SELECT * FROM review_results WHERE submission_id = $1 AND NOT superseded ORDER BY reviewer_name",
     )
     .bind(submission_id)
     .fetch_all(pool)
@@ -69,14 +454,313 @@ pub async fn get_review_results(
     let mut map = std::collections::HashMap::new();
     for r in rows {
         // Note: filename omitted - per-review PDFs not generated; full review shown in page
-        let value = serde_json::json!({
-            "decision": r.decision,
-            "summary": r.summary.unwrap_or_default(),
-            "full_review": r.full_review.unwrap_or_default(),
-            "model_used": r.model_used,
-            "model_downgraded": false
-        });
+        // Fallback moved the reviewer off the model it started on
+        let model_downgraded = r
+            .model_used
+            .as_deref()
+            .zip(r.model_sequence.first())
+            .is_some_and(|(used, first)| used != first);
+        let value = ReviewResultDisplay {
+            decision: r.decision,
+            summary: r.summary.unwrap_or_default(),
+            full_review: r.full_review.unwrap_or_default(),
+            model_used: r.model_used,
+            model_downgraded,
+            ambiguous: r.ambiguous,
+            structured_review: r.structured_review,
+            created_at: r.created_at,
+            model_sequence: r.model_sequence,
+            reasons: r.reasons,
+            weighted_score: r.weighted_score,
+            confidence: r.confidence,
+        };
         map.insert(r.reviewer_name, value);
     }
     Ok(map)
 }
+
+/// Queues `submission_id` for review, resetting a finished or failed job.
+pub async fn enqueue_review_job(pool: &PgPool, submission_id: &str) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO review_jobs (submission_id) VALUES ($1)
+        ON CONFLICT (submission_id) DO UPDATE
+        SET status = 'queued', attempts = 0, next_run_at = NOW(), locked_until = NULL,
+            last_error = NULL, updated_at = NOW()
+        "#,
+    )
+    .bind(submission_id)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// True while a worker is running the submission's review job.
+pub async fn review_job_running(pool: &PgPool, submission_id: &str) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar::<_, bool>(
+        "SELECT EXISTS (SELECT 1 FROM review_jobs WHERE submission_id = $1 AND status = 'running')",
+    )
+    .bind(submission_id)
+    .fetch_one(pool)
+    .await
+}
+
+/// Claims the next due job: a queued one whose `next_run_at` has passed, or
+/// a running one whose lock lapsed (its worker died). The job stays locked
+/// for `lock_secs`; other workers skip it meanwhile.
+pub async fn claim_review_job(
+    pool: &PgPool,
+    lock_secs: u64,
+) -> Result<Option<ReviewJob>, sqlx::Error> {
+    sqlx::query_as::<_, ReviewJob>(
+        r#"
+        UPDATE review_jobs
+        SET status = 'running', attempts = attempts + 1,
+            locked_until = NOW() + make_interval(secs => $1), updated_at = NOW()
+        WHERE id = (
+            SELECT id FROM review_jobs
+            WHERE (status = 'queued' AND next_run_at <= NOW())
+               OR (status = 'running' AND locked_until < NOW())
+            ORDER BY next_run_at
+            LIMIT 1
+            FOR UPDATE SKIP LOCKED
+        )
+        RETURNING id, submission_id, attempts
+        "#,
+    )
+    .bind(lock_secs as f64)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Keeps a running job locked for another `lock_secs`.
+pub async fn extend_review_job(pool: &PgPool, id: i32, lock_secs: u64) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE review_jobs SET locked_until = NOW() + make_interval(secs => $2), updated_at = NOW()
+        WHERE id = $1 AND status = 'running'
+        "#,
+    )
+    .bind(id)
+    .bind(lock_secs as f64)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+pub async fn complete_review_job(pool: &PgPool, id: i32) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "UPDATE review_jobs SET status = 'done', locked_until = NULL, updated_at = NOW() WHERE id = $1",
+    )
+    .bind(id)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Puts a failed job back in the queue to run again after `delay_secs`.
+pub async fn reschedule_review_job(
+    pool: &PgPool,
+    id: i32,
+    error: &str,
+    delay_secs: u64,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE review_jobs
+        SET status = 'queued', next_run_at = NOW() + make_interval(secs => $3),
+            locked_until = NULL, last_error = $2, updated_at = NOW()
+        WHERE id = $1
+        "#,
+    )
+    .bind(id)
+    .bind(error)
+    .bind(delay_secs as f64)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Gives up on a job, recording the error on the job and finishing its
+/// submission as failed, in one transaction.
+pub async fn fail_review_job(
+    pool: &PgPool,
+    id: i32,
+    submission_id: &str,
+    error: &str,
+) -> Result<(), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    sqlx::query(
+        r#"
+        UPDATE review_jobs SET status = 'failed', locked_until = NULL, last_error = $2, updated_at = NOW()
+        WHERE id = $1
+        "#,
+    )
+    .bind(id)
+    .bind(error)
+    .execute(&mut *tx)
+    .await?;
+    sqlx::query(
+        "UPDATE submissions SET error = $2, processing_complete = true WHERE submission_id = $1",
+    )
+    .bind(submission_id)
+    .bind(error)
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await
+}
+
+/// Files the retention sweep removes; see [`files_due_for_deletion`].
+#[derive(Clone, Copy)]
+pub enum StoredFiles {
+    Upload,
+    Results,
+}
+
+impl StoredFiles {
+    fn deleted_column(self) -> &'static str {
+        match self {
+            StoredFiles::Upload => "upload_deleted_at",
+            StoredFiles::Results => "results_deleted_at",
+        }
+    }
+}
+
+/// Finished submissions older than `days` whose `kind` files are still on
+/// disk, as `(submission_id, file_path)`, oldest first.
+pub async fn files_due_for_deletion(
+    pool: &PgPool,
+    kind: StoredFiles,
+    days: i32,
+    limit: i64,
+) -> Result<Vec<(String, String)>, sqlx::Error> {
+    sqlx::query_as(&format!(
+        r#"
+        SELECT submission_id, file_path FROM submissions
+        WHERE processing_complete AND {} IS NULL
+          AND created_at < NOW() - make_interval(days => $1)
+        ORDER BY created_at
+        LIMIT $2
+        "#,
+        kind.deleted_column()
+    ))
+    .bind(days)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn mark_files_deleted(
+    pool: &PgPool,
+    kind: StoredFiles,
+    submission_id: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(&format!(
+        "UPDATE submissions SET {} = NOW() WHERE submission_id = $1",
+        kind.deleted_column()
+    ))
+    .bind(submission_id)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Review text and structured JSON cached under `key`, if any.
+pub async fn cached_review(
+    pool: &PgPool,
+    key: &ReviewCacheKey<'_>,
+) -> Result<Option<(String, Option<serde_json::Value>)>, sqlx::Error> {
+    sqlx::query_as::<_, (String, Option<serde_json::Value>)>(
+        r#"
+        SELECT review_text, structured_review FROM review_cache
+        WHERE content_hash = $1 AND prompt_hash = $2 AND model = $3 AND reviewer_name = $4
+        "#,
+    )
+    .bind(key.content_hash)
+    .bind(key.prompt_hash)
+    .bind(key.model)
+    .bind(key.reviewer_name)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Stores a review under `key`, replacing any earlier entry (a forced refresh).
+pub async fn store_cached_review(
+    pool: &PgPool,
+    key: &ReviewCacheKey<'_>,
+    review_text: &str,
+    structured_review: Option<&serde_json::Value>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO review_cache (content_hash, prompt_hash, model, reviewer_name, review_text, structured_review)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        ON CONFLICT (content_hash, prompt_hash, model, reviewer_name)
+        DO UPDATE SET review_text = EXCLUDED.review_text,
+                      structured_review = EXCLUDED.structured_review,
+                      created_at = NOW()
+        "#,
+    )
+    .bind(key.content_hash)
+    .bind(key.prompt_hash)
+    .bind(key.model)
+    .bind(key.reviewer_name)
+    .bind(review_text)
+    .bind(structured_review)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Appends one entry to the audit log.
+pub async fn insert_audit_entry(
+    pool: &PgPool,
+    action: &str,
+    submission_id: Option<&str>,
+    actor: &str,
+    outcome: &str,
+    detail: Option<&str>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO audit_log (action, submission_id, actor, outcome, detail)
+        VALUES ($1, $2, $3, $4, $5)
+        "#,
+    )
+    .bind(action)
+    .bind(submission_id)
+    .bind(actor)
+    .bind(outcome)
+    .bind(detail)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Audit entries matching `filter`, newest first.
+pub async fn list_audit_entries(
+    pool: &PgPool,
+    filter: &AuditFilter<'_>,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<AuditEntry>, sqlx::Error> {
+    sqlx::query_as::<_, AuditEntry>(
+        r#"
+        SELECT * FROM audit_log
+        WHERE ($1::TEXT IS NULL OR action = $1)
+          AND ($2::TEXT IS NULL OR submission_id = $2)
+          AND ($3::TEXT IS NULL OR actor = $3)
+          AND ($4::TIMESTAMPTZ IS NULL OR created_at >= $4)
+        ORDER BY id DESC
+        LIMIT $5 OFFSET $6
+        "#,
+    )
+    .bind(filter.action)
+    .bind(filter.submission_id)
+    .bind(filter.actor)
+    .bind(filter.since)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(pool)
+    .await
+}
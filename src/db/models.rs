@@ -2,7 +2,7 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 
-#[derive(Debug, FromRow, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, FromRow, Serialize, Deserialize)]
 pub struct Submission {
     pub id: i32,
     pub submission_id: String,
@@ -14,6 +14,80 @@ pub struct Submission {
     pub all_accepted: bool,
     pub error: Option<String>,
     pub certificate_filename: Option<String>,
+    pub category: Option<String>,
+    pub certificate_issued_at: Option<DateTime<Utc>>,
+    pub detected_language: Option<String>,
+    pub flags: Vec<String>,
+    pub content_type: Option<String>,
+    pub certificate_kind: Option<String>,
+    #[serde(skip_serializing)]
+    pub author_token_hash: Option<String>,
+    /// Tokens across all review requests; zero unless `STORE_TOKEN_USAGE`.
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    /// Earlier submission this one revises.
+    pub parent_submission_id: Option<String>,
+    /// Results and downloads are refused after this time.
+    pub expires_at: Option<DateTime<Utc>>,
+    /// Mean pairwise word-set similarity of the final reviews.
+    pub review_similarity: Option<f64>,
+    /// Plain-language outcome for the authors (`AUTHOR_SUMMARY`).
+    pub author_summary: Option<String>,
+    /// Word-likeness (0.0-1.0) of the extracted text.
+    pub extraction_quality: Option<f64>,
+    /// Why an earned certificate could not be generated.
+    pub certificate_error: Option<String>,
+    /// One reviewer accepted the paper while another rejected it.
+    pub reviewer_disagreement: bool,
+    /// Escalated under `DISAGREEMENT_POLICY=needs_human`; not auto-decided.
+    pub needs_human_review: bool,
+    /// Pages in the uploaded PDF.
+    pub page_count: Option<i32>,
+    /// When `UPLOAD_RETENTION_DAYS` removed the uploaded PDF.
+    pub upload_deleted_at: Option<DateTime<Utc>>,
+    /// When `RESULTS_RETENTION_DAYS` removed the certificate and other files.
+    pub results_deleted_at: Option<DateTime<Utc>>,
+    /// Venue the submission belongs to; `None` when multi-tenancy is off.
+    pub tenant_id: Option<String>,
+    /// Active PDF features found at upload (`javascript`, `open_action`, ...).
+    pub active_content: Vec<String>,
+    /// Lowest confidence among the final reviews that had scores.
+    pub decision_confidence: Option<f64>,
+}
+
+/// Column values for a new submission row.
+pub struct NewSubmission<'a> {
+    pub submission_id: &'a str,
+    pub paper_title: &'a str,
+    pub filename: &'a str,
+    pub file_path: &'a str,
+    pub category: Option<&'a str>,
+    pub detected_language: Option<&'a str>,
+    pub flags: &'a [String],
+    pub content_type: Option<&'a str>,
+    pub author_token_hash: &'a str,
+    pub parent_submission_id: Option<&'a str>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub extraction_quality: f64,
+    pub page_count: i32,
+    pub tenant_id: Option<&'a str>,
+    pub active_content: &'a [&'a str],
+}
+
+/// Progress of one submission, as reported by the batch status endpoint.
+#[derive(Debug, FromRow)]
+pub struct SubmissionProgress {
+    pub submission_id: String,
+    pub processing_complete: bool,
+    pub all_accepted: bool,
+    pub certificate_filename: Option<String>,
+    pub certificate_kind: Option<String>,
+    pub author_token_hash: Option<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub tenant_id: Option<String>,
+    pub completed_reviews: i64,
+    /// A queue worker has picked up the submission's review job.
+    pub review_started: bool,
 }
 
 #[derive(Debug, FromRow, Serialize, Deserialize)]
@@ -27,6 +101,22 @@ pub struct ReviewResult {
     pub model_used: Option<String>,
     pub file_url: Option<String>,
     pub created_at: DateTime<Utc>,
+    pub ambiguous: bool,
+    pub structured_review: Option<serde_json::Value>,
+    pub model_sequence: Vec<String>,
+    pub attempt_log: Option<serde_json::Value>,
+    /// Replaced by a later retry of the same reviewer.
+    pub superseded: bool,
+    /// Key weaknesses behind a REJECTED or REVISION decision.
+    pub reasons: Vec<String>,
+    /// `REVIEW_SEED` in effect when the review was generated.
+    pub seed: Option<i64>,
+    /// Reused from `review_cache` rather than generated for this submission.
+    pub cached: bool,
+    /// Weighted criterion score from the structured output, when present.
+    pub weighted_score: Option<f64>,
+    /// 0 when `weighted_score` sits on a decision threshold, up to 1 far from one.
+    pub confidence: Option<f64>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -36,4 +126,51 @@ pub struct ReviewResultDisplay {
     pub full_review: String,
     pub model_used: Option<String>,
     pub model_downgraded: bool,
+    pub ambiguous: bool,
+    pub structured_review: Option<serde_json::Value>,
+    pub created_at: DateTime<Utc>,
+    pub model_sequence: Vec<String>,
+    pub reasons: Vec<String>,
+    pub weighted_score: Option<f64>,
+    pub confidence: Option<f64>,
+}
+
+/// A claimed `review_jobs` row.
+#[derive(Debug, FromRow)]
+pub struct ReviewJob {
+    pub id: i32,
+    pub submission_id: String,
+    /// Claims so far, including the current one.
+    pub attempts: i32,
+}
+
+/// Identifies a cached review: the paper text, the prompt and settings that
+/// shaped the request, the model that answered, and the reviewer slot.
+pub struct ReviewCacheKey<'a> {
+    pub content_hash: &'a str,
+    pub prompt_hash: &'a str,
+    pub model: &'a str,
+    pub reviewer_name: &'a str,
+}
+
+/// One `audit_log` row.
+#[derive(Debug, FromRow, Serialize)]
+pub struct AuditEntry {
+    pub id: i64,
+    pub created_at: DateTime<Utc>,
+    pub action: String,
+    pub submission_id: Option<String>,
+    pub actor: String,
+    pub outcome: String,
+    pub detail: Option<String>,
+}
+
+/// Filters for [`list_audit_entries`](super::list_audit_entries); unset
+/// fields match everything.
+#[derive(Default)]
+pub struct AuditFilter<'a> {
+    pub action: Option<&'a str>,
+    pub submission_id: Option<&'a str>,
+    pub actor: Option<&'a str>,
+    pub since: Option<DateTime<Utc>>,
 }
@@ -0,0 +1,43 @@
+// Submission lifecycle events broadcast to live monitors (see routes::ws_events)
+use serde::Serialize;
+use tokio::sync::broadcast;
+
+/// Events a slow subscriber may fall behind by before older ones are dropped.
+pub const EVENT_CHANNEL_CAPACITY: usize = 256;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum SubmissionEvent {
+    Created {
+        submission_id: String,
+        paper_title: String,
+    },
+    ReviewCompleted {
+        submission_id: String,
+        reviewer_name: String,
+        decision: String,
+    },
+    Finished {
+        submission_id: String,
+        all_accepted: bool,
+    },
+}
+
+impl SubmissionEvent {
+    pub fn submission_id(&self) -> &str {
+        match self {
+            SubmissionEvent::Created { submission_id, .. }
+            | SubmissionEvent::ReviewCompleted { submission_id, .. }
+            | SubmissionEvent::Finished { submission_id, .. } => submission_id,
+        }
+    }
+}
+
+pub fn channel() -> broadcast::Sender<SubmissionEvent> {
+    broadcast::channel(EVENT_CHANNEL_CAPACITY).0
+}
+
+/// Publishes an event; having no subscribers is not an error.
+pub fn publish(sender: &broadcast::Sender<SubmissionEvent>, event: SubmissionEvent) {
+    let _ = sender.send(event);
+}
@@ -1,18 +1,30 @@
 mod agents;
+mod audit;
 mod config;
 mod db;
+mod events;
+mod markdown;
 mod pdf;
+mod queue;
+mod retention;
 mod routes;
 mod state;
 mod storage;
 mod templates;
+#[cfg(test)]
+mod test_support;
 
 use axum::{
-    routing::{get, post},
+    extract::DefaultBodyLimit,
+    routing::{get, patch, post},
     Router,
 };
+use std::future::IntoFuture;
+use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::Duration;
 use tower_http::cors::CorsLayer;
+use tower_http::timeout::TimeoutLayer;
 use tower_http::trace::TraceLayer;
 
 #[tokio::main]
@@ -27,35 +39,190 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     let config = config::Config::from_env()?;
     let config = Arc::new(config);
 
-    crate::storage::ensure_dirs(&config.upload_folder, &config.results_folder)?;
+    crate::storage::ensure_dirs(
+        &config.upload_folder,
+        &config.results_folder,
+        &config.temp_folder,
+        config.dir_mode,
+    )?;
 
-    let pool = db::create_pool(&config.database_url).await?;
+    let pool = db::create_pool(&config.database_url, config.db_statement_timeout_ms).await?;
     db::run_migrations(pool.as_ref()).await?;
 
+    let http = agents::build_http_client(&config)?;
+
+    // Only a quota needs the usage total; skip the scan otherwise
+    let storage_usage = if config.storage_quota_bytes.is_some() {
+        storage::StorageUsage::scan(&[&config.upload_folder, &config.results_folder])
+    } else {
+        storage::StorageUsage::default()
+    };
+
     let state = Arc::new(state::AppState {
         pool,
         config: config.clone(),
+        http: http.clone(),
+        events: events::channel(),
+        submission_locks: state::SubmissionLocks::default(),
+        in_flight_uploads: state::InFlightUploads::default(),
+        maintenance: Arc::new(state::MaintenanceMode::new(config.maintenance_mode)),
+        storage_usage: Arc::new(storage_usage),
+        batches: config
+            .batch_reviews
+            .then(|| agents::BatchCoordinator::start(&config, http.clone())),
+        review_queue: Arc::new(queue::ReviewQueue::default()),
+        status_cache: state::StatusCache::new(Duration::from_millis(config.status_cache_ttl_ms)),
+        extraction_pool: agents::ExtractionPool::new(config.extraction_workers),
     });
+    queue::start_workers(&state);
+    retention::start(&state);
 
-    let app = Router::new()
+    // Management routes; served on ADMIN_BIND when set so they stay off the
+    // public listener
+    let mut admin = Router::new()
+        .route("/api/submissions", get(routes::list_submissions))
+        .route(
+            "/api/maintenance",
+            get(routes::maintenance_status).post(routes::set_maintenance),
+        )
+        .route(
+            "/api/submissions/:submission_id/reviews",
+            get(routes::list_reviews),
+        )
+        .route(
+            "/api/submissions/:submission_id/reviews/:reviewer_name",
+            get(routes::get_review),
+        )
+        .route(
+            "/api/submissions/:submission_id/reviews/:reviewer_name/summary",
+            post(routes::regenerate_review_summary),
+        )
+        .route(
+            "/api/submissions/:submission_id/paper",
+            get(routes::admin_download_paper),
+        )
+        .route("/api/audit", get(routes::list_audit_log))
+        .route("/api/events", get(routes::ws_operator_events));
+
+    // PUBLIC_PROMPT=false keeps the prompt with the management routes
+    if !config.public_prompt {
+        admin = admin.route("/api/prompt", get(routes::review_prompt));
+    }
+
+    let mut app = Router::new()
         .route("/", get(routes::index))
         .route("/upload", post(routes::upload_handler))
         .route("/results/:submission_id", get(routes::view_results))
-        .route("/api/status/:submission_id", get(routes::check_status))
-        .route("/download/:filename", get(routes::download_file))
-        .route("/download_certificate/:submission_id", get(routes::download_certificate))
+        .route(
+            "/submission/:submission_id",
+            patch(routes::update_submission),
+        )
+        .route("/api/status/batch", post(routes::batch_status))
+        .route(
+            "/download/:submission_id/:filename",
+            get(routes::download_file),
+        )
+        .route(
+            "/download_certificate/:submission_id",
+            get(routes::download_certificate),
+        )
         .route("/download_all/:submission_id", get(routes::download_all))
-        .route("/retry_review/:submission_id/:reviewer_name", post(routes::retry_review))
-        .nest_service("/static", tower_http::services::ServeDir::new("static"))
-        .layer(TraceLayer::new_for_http())
-        .layer(CorsLayer::permissive())
-        .with_state(state);
+        .route(
+            "/download_paper/:submission_id",
+            get(routes::download_paper),
+        )
+        .route(
+            "/api/submissions/:submission_id/artifacts",
+            get(routes::list_artifacts),
+        )
+        .route("/preview/:submission_id", get(routes::paper_preview))
+        .route("/verify/:submission_id", get(routes::verify_certificate))
+        .route("/static/*path", get(routes::serve_static));
+    if config.public_prompt {
+        app = app.route("/api/prompt", get(routes::review_prompt));
+    }
+
+    // Routes that may legitimately outlast REQUEST_TIMEOUT_SECS: long-poll
+    // status, the WebSocket, and reviews run inline (sync submit, retry)
+    let untimed = Router::new()
+        .route("/api/submit", post(routes::submit))
+        .route("/api/status/:submission_id", get(routes::check_status))
+        .route(
+            "/retry_review/:submission_id/:reviewer_name",
+            post(routes::retry_review),
+        )
+        .route("/ws", get(routes::ws_submission_events));
+    if let Some(secs) = config.request_timeout_secs {
+        app = app.layer(TimeoutLayer::new(Duration::from_secs(secs)));
+        admin = admin.layer(TimeoutLayer::new(Duration::from_secs(secs)));
+    }
+    let admin = admin.layer(axum::middleware::from_fn_with_state(
+        state.clone(),
+        routes::require_admin,
+    ));
+    let app = app
+        .merge(untimed)
+        .layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            routes::require_tenant,
+        ))
+        .layer(DefaultBodyLimit::max(config.max_upload_bytes))
+        .layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            routes::friendly_payload_too_large,
+        ));
 
     let addr = format!("{}:{}", config.host, config.port);
+    let listener = tokio::net::TcpListener::bind(&addr).await?;
     tracing::info!("Cuadrada listening on http://{}", addr);
 
-    let listener = tokio::net::TcpListener::bind(&addr).await?;
-    axum::serve(listener, app).await?;
+    match &config.admin_bind {
+        Some(admin_addr) => {
+            let app = app
+                .layer(TraceLayer::new_for_http())
+                .layer(CorsLayer::permissive())
+                .with_state(state.clone());
+            // No CORS layer: the admin listener is never called from browsers
+            let admin = admin.layer(TraceLayer::new_for_http()).with_state(state);
+
+            let admin_listener = tokio::net::TcpListener::bind(admin_addr).await?;
+            tracing::info!("Admin routes listening on http://{}", admin_addr);
+
+            tokio::try_join!(
+                axum::serve(
+                    listener,
+                    app.into_make_service_with_connect_info::<SocketAddr>()
+                )
+                .into_future(),
+                axum::serve(
+                    admin_listener,
+                    admin.into_make_service_with_connect_info::<SocketAddr>()
+                )
+                .into_future(),
+            )?;
+        }
+        None => {
+            // Never expose unauthenticated management routes publicly
+            let app = if config.admin_token.is_some() {
+                app.merge(admin)
+            } else {
+                tracing::warn!(
+                    "Management routes are disabled; set ADMIN_TOKEN or ADMIN_BIND to enable them"
+                );
+                app
+            };
+            let app = app
+                .layer(TraceLayer::new_for_http())
+                .layer(CorsLayer::permissive())
+                .with_state(state);
+            // Peer addresses identify the actor in the audit log
+            axum::serve(
+                listener,
+                app.into_make_service_with_connect_info::<SocketAddr>(),
+            )
+            .await?;
+        }
+    }
 
     Ok(())
 }
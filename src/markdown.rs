@@ -0,0 +1,57 @@
+// Markdown-to-HTML rendering for model-written reviews, on pulldown-cmark.
+// Raw HTML in the source is passed through as escaped text and links and
+// images are reduced to their text, so model output cannot inject markup.
+use pulldown_cmark::{html, Event, Options, Parser, Tag, TagEnd};
+
+/// Renders CommonMark (headings, lists, paragraphs, emphasis, code) to HTML.
+/// Line breaks inside a paragraph are kept.
+pub fn render_markdown(text: &str) -> String {
+    let events = Parser::new_ext(text, Options::empty()).filter_map(|event| match event {
+        Event::Html(raw) | Event::InlineHtml(raw) => Some(Event::Text(raw)),
+        Event::SoftBreak => Some(Event::HardBreak),
+        Event::Start(Tag::Link { .. } | Tag::Image { .. })
+        | Event::End(TagEnd::Link | TagEnd::Image) => None,
+        event => Some(event),
+    });
+    let mut out = String::new();
+    html::push_html(&mut out, events);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn raw_html_in_a_review_comes_out_escaped() {
+        let html = render_markdown(
+            "Good paper.\n\n<script>alert(1)</script>\n\nSee <img src=x onerror=alert(1)> here.",
+        );
+        assert!(!html.contains("<script"), "{}", html);
+        assert!(!html.contains("<img"), "{}", html);
+        assert!(
+            html.contains("&lt;script&gt;alert(1)&lt;/script&gt;"),
+            "{}",
+            html
+        );
+        assert!(
+            html.contains("&lt;img src=x onerror=alert(1)&gt;"),
+            "{}",
+            html
+        );
+    }
+
+    #[test]
+    fn emphasis_is_not_applied_inside_code_spans() {
+        assert_eq!(
+            render_markdown("Use `a*b*c` and **this**."),
+            "<p>Use <code>a*b*c</code> and <strong>this</strong>.</p>\n"
+        );
+    }
+
+    #[test]
+    fn links_and_images_are_reduced_to_their_text() {
+        let html = render_markdown("[click](javascript:alert(1)) ![chart](http://x/y.png)");
+        assert_eq!(html, "<p>click chart</p>\n");
+    }
+}
@@ -1,9 +1,67 @@
 // Certificate PDF generation
 // Uses genpdf - requires Liberation or similar fonts in standard paths
-use genpdf::*;
-use std::path::Path;
+use chrono::{DateTime, Utc};
+use genpdf::Element;
 
-pub fn generate_certificate(paper_title: &str, output_path: &Path) -> Result<(), String> {
+mod preview;
+
+pub use preview::*;
+
+/// Which outcome a certificate documents.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CertificateKind {
+    /// Every reviewer accepted the paper.
+    Accepted,
+    /// Every reviewer accepted, some with minor revisions.
+    MinorRevision,
+}
+
+impl CertificateKind {
+    /// Value stored in `submissions.certificate_kind`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CertificateKind::Accepted => "accepted",
+            CertificateKind::MinorRevision => "minor_revision",
+        }
+    }
+
+    /// Inverse of [`CertificateKind::as_str`].
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "accepted" => Some(CertificateKind::Accepted),
+            "minor_revision" => Some(CertificateKind::MinorRevision),
+            _ => None,
+        }
+    }
+
+    fn heading(self) -> &'static str {
+        match self {
+            CertificateKind::Accepted => "Certificate of Acceptance",
+            CertificateKind::MinorRevision => "Certificate of Acceptance with Minor Revisions",
+        }
+    }
+
+    fn statement(self) -> &'static str {
+        match self {
+            CertificateKind::Accepted => {
+                "has successfully passed Cuadrada's AI-powered peer review process"
+            }
+            CertificateKind::MinorRevision => {
+                "has been accepted with minor revisions through Cuadrada's AI-powered peer review process"
+            }
+        }
+    }
+}
+
+/// Renders the certificate and returns the PDF bytes; callers decide where
+/// and how to store them.
+pub fn generate_certificate(
+    paper_title: &str,
+    certificate_id: &str,
+    kind: CertificateKind,
+    issued_at: DateTime<Utc>,
+    expires_at: Option<DateTime<Utc>>,
+) -> Result<Vec<u8>, String> {
     // Try common font paths - genpdf needs actual font files for metrics
     let font_paths = [
         "/usr/share/fonts/truetype/liberation",
@@ -25,36 +83,35 @@ pub fn generate_certificate(paper_title: &str, output_path: &Path) -> Result<(),
         })?;
 
     let mut doc = genpdf::Document::new(font_family);
-    doc.set_title("Certificate of Acceptance");
+    doc.set_title(kind.heading());
 
     let mut decorator = genpdf::SimplePageDecorator::new();
     decorator.set_margins(10);
     doc.set_page_decorator(decorator);
 
     let title_style = genpdf::style::Style::new().with_font_size(24);
-    doc.push(genpdf::elements::Paragraph::new("Certificate of Acceptance").styled(title_style));
-
-    let title = if paper_title.len() > 80 {
-        format!("{}...", &paper_title[..80])
-    } else {
-        paper_title.to_string()
-    };
-    doc.push(genpdf::elements::Paragraph::new(&title));
+    doc.push(genpdf::elements::Paragraph::new(kind.heading()).styled(title_style));
+
+    doc.push(genpdf::elements::Paragraph::new(paper_title));
     doc.push(genpdf::elements::Break::new(0.5));
-    doc.push(genpdf::elements::Paragraph::new(
-        "has successfully passed Cuadrada's AI-powered peer review process",
-    ));
+    doc.push(genpdf::elements::Paragraph::new(kind.statement()));
     doc.push(genpdf::elements::Break::new(0.5));
 
-    let date = chrono::Utc::now().format("%B %d, %Y").to_string();
-    let id = output_path
-        .file_stem()
-        .and_then(|s| s.to_str())
-        .unwrap_or("unknown")
-        .replace("_certificate", "");
+    let date = issued_at.format("%B %d, %Y").to_string();
 
     doc.push(genpdf::elements::Paragraph::new(format!("Date: {}", date)));
-    doc.push(genpdf::elements::Paragraph::new(format!("Certificate ID: {}", id)));
+    if let Some(expires_at) = expires_at {
+        doc.push(genpdf::elements::Paragraph::new(format!(
+            "Expires: {}",
+            expires_at.format("%B %d, %Y")
+        )));
+    }
+    doc.push(genpdf::elements::Paragraph::new(format!(
+        "Certificate ID: {}",
+        certificate_id
+    )));
 
-    doc.render_to_file(output_path).map_err(|e| e.to_string())
+    let mut buffer = Vec::new();
+    doc.render(&mut buffer).map_err(|e| e.to_string())?;
+    Ok(buffer)
 }
@@ -0,0 +1,56 @@
+// First-page PNG renderings (paper previews, certificate images) made by
+// poppler's pdftoppm
+use std::path::Path;
+use std::time::Duration;
+
+/// Width in pixels of a results-page preview.
+pub const PREVIEW_WIDTH: u32 = 400;
+
+/// Width in pixels of a certificate rendered as an image.
+pub const CERTIFICATE_IMAGE_WIDTH: u32 = 1600;
+
+/// Longest a single render may take before it is abandoned.
+const RENDER_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Renders the first page of `pdf` to a PNG `width` pixels wide at `dest`,
+/// with permissions `mode` if given. The image is written in `temp_folder`
+/// and renamed into place, so `dest` is never partial.
+pub async fn render_first_page(
+    pdftoppm: &str,
+    pdf: &Path,
+    width: u32,
+    temp_folder: &Path,
+    dest: &Path,
+    mode: Option<u32>,
+) -> Result<(), String> {
+    let prefix = temp_folder.join(format!("preview_{}", uuid::Uuid::new_v4().simple()));
+    let output = tokio::process::Command::new(pdftoppm)
+        .args(["-png", "-singlefile", "-f", "1", "-l", "1", "-scale-to-x"])
+        .arg(width.to_string())
+        .args(["-scale-to-y", "-1"])
+        .arg(pdf)
+        .arg(&prefix)
+        .kill_on_drop(true)
+        .output();
+    let output = tokio::time::timeout(RENDER_TIMEOUT, output)
+        .await
+        .map_err(|_| "preview rendering timed out".to_string())?
+        .map_err(|e| format!("failed to run {}: {}", pdftoppm, e))?;
+
+    let rendered = prefix.with_extension("png");
+    if !output.status.success() {
+        let _ = std::fs::remove_file(&rendered);
+        return Err(format!(
+            "{} exited with {}: {}",
+            pdftoppm,
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    crate::storage::set_mode(&rendered, mode)
+        .and_then(|()| std::fs::rename(&rendered, dest))
+        .map_err(|e| {
+            let _ = std::fs::remove_file(&rendered);
+            e.to_string()
+        })
+}
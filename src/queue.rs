@@ -0,0 +1,174 @@
+// Durable review queue: submissions are recorded in `review_jobs` and a fixed
+// pool of workers claims them, so queued work survives a restart
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::Notify;
+
+use crate::db::ReviewJob;
+use crate::state::{AppState, InFlightGuard};
+
+/// How often an idle worker looks for due jobs without being woken, which
+/// picks up rescheduled jobs and ones whose worker died.
+const IDLE_POLL: Duration = Duration::from_secs(5);
+
+/// Delay before the first retry of a failed job; doubles per attempt.
+const RETRY_BASE_SECS: u64 = 60;
+
+#[derive(Default)]
+pub struct ReviewQueue {
+    wake: Notify,
+    /// Duplicate-upload guards of queued submissions, released when their
+    /// job finishes. Lost on restart, like the in-flight set itself.
+    in_flight: Mutex<HashMap<String, InFlightGuard>>,
+}
+
+impl ReviewQueue {
+    /// Records a job for `submission_id` and wakes a worker.
+    pub async fn enqueue(
+        &self,
+        pool: &sqlx::PgPool,
+        submission_id: &str,
+        guard: InFlightGuard,
+    ) -> Result<(), sqlx::Error> {
+        crate::db::enqueue_review_job(pool, submission_id).await?;
+        self.in_flight
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(submission_id.to_string(), guard);
+        self.wake.notify_one();
+        Ok(())
+    }
+
+    fn release(&self, submission_id: &str) {
+        self.in_flight
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(submission_id);
+    }
+}
+
+/// Starts `REVIEW_WORKERS` workers; they run for the life of the process.
+pub fn start_workers(state: &Arc<AppState>) {
+    for worker in 1..=state.config.review_workers {
+        tokio::spawn(run_worker(state.clone(), worker));
+    }
+}
+
+async fn run_worker(state: Arc<AppState>, worker: usize) {
+    loop {
+        match crate::db::claim_review_job(state.pool.as_ref(), state.config.review_job_lock_secs)
+            .await
+        {
+            Ok(Some(job)) => run_job(&state, worker, job).await,
+            Ok(None) => {
+                tokio::select! {
+                    _ = state.review_queue.wake.notified() => {}
+                    _ = tokio::time::sleep(IDLE_POLL) => {}
+                }
+            }
+            Err(e) => {
+                tracing::error!("Review worker {} failed to claim a job: {}", worker, e);
+                tokio::time::sleep(IDLE_POLL).await;
+            }
+        }
+    }
+}
+
+async fn run_job(state: &AppState, worker: usize, job: ReviewJob) {
+    tracing::info!(
+        "Review worker {} processing {} (attempt {})",
+        worker,
+        job.submission_id,
+        job.attempts
+    );
+    // Status moves from queued to processing
+    state.status_cache.invalidate(&job.submission_id);
+    let lock_secs = state.config.review_job_lock_secs;
+    // Renew the lock while reviews run so no other worker reclaims the job
+    let heartbeat = {
+        let pool = state.pool.clone();
+        let id = job.id;
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(Duration::from_secs((lock_secs / 3).max(1))).await;
+                if let Err(e) = crate::db::extend_review_job(pool.as_ref(), id, lock_secs).await {
+                    tracing::warn!("Failed to extend lock on review job {}: {}", id, e);
+                }
+            }
+        })
+    };
+    let result = crate::routes::run_review_job(state, &job.submission_id).await;
+    heartbeat.abort();
+
+    let pool = state.pool.as_ref();
+    let recorded = match result {
+        Ok(()) => {
+            state.review_queue.release(&job.submission_id);
+            crate::db::complete_review_job(pool, job.id).await
+        }
+        Err(e) if !attempts_exhausted(job.attempts, state.config.review_job_max_attempts) => {
+            let delay = RETRY_BASE_SECS << (job.attempts.max(1) - 1).min(10) as u32;
+            tracing::warn!(
+                "Review job for {} failed (attempt {}): {}; retrying in {}s",
+                job.submission_id,
+                job.attempts,
+                e,
+                delay
+            );
+            crate::db::reschedule_review_job(pool, job.id, &e, delay).await
+        }
+        Err(e) => {
+            tracing::error!(
+                "Review job for {} failed after {} attempts: {}",
+                job.submission_id,
+                job.attempts,
+                e
+            );
+            state.review_queue.release(&job.submission_id);
+            let failed = crate::db::fail_review_job(pool, job.id, &job.submission_id, &e).await;
+            state.status_cache.invalidate(&job.submission_id);
+            if failed.is_ok() {
+                crate::events::publish(
+                    &state.events,
+                    crate::events::SubmissionEvent::Finished {
+                        submission_id: job.submission_id.clone(),
+                        all_accepted: false,
+                    },
+                );
+            }
+            failed
+        }
+    };
+    if let Err(e) = recorded {
+        tracing::error!("Failed to update review job {}: {}", job.id, e);
+    }
+}
+
+/// True once a job has used all `max_attempts` runs and is failed for good.
+fn attempts_exhausted(attempts: i32, max_attempts: i32) -> bool {
+    attempts >= max_attempts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn job_past_max_attempts_reports_failed() {
+        assert!(!attempts_exhausted(2, 3));
+        assert!(attempts_exhausted(3, 3));
+        assert!(attempts_exhausted(4, 3));
+
+        // The row as fail_review_job leaves it
+        let submission = crate::db::Submission {
+            submission_id: "sub".to_string(),
+            processing_complete: true,
+            error: Some("Claude API unavailable".to_string()),
+            ..Default::default()
+        };
+        let status = crate::routes::complete_status(&submission, &Default::default());
+        assert_eq!(status["status"], "failed");
+        assert_eq!(status["error"], "Claude API unavailable");
+    }
+}
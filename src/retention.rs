@@ -0,0 +1,129 @@
+// Retention sweep: deletes uploaded PDFs after UPLOAD_RETENTION_DAYS and
+// result files (certificates, previews) after RESULTS_RETENTION_DAYS. The
+// submission and review rows stay, so results pages keep working.
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::audit;
+use crate::db::StoredFiles;
+use crate::state::AppState;
+
+const SWEEP_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// Submissions handled per category and sweep; the rest wait for the next one.
+const SWEEP_BATCH: i64 = 500;
+
+/// Starts the hourly sweep when either retention period is set.
+pub fn start(state: &Arc<AppState>) {
+    let uploads = state.config.upload_retention_days;
+    let results = state.config.results_retention_days;
+    if uploads.is_none() && results.is_none() {
+        return;
+    }
+    let state = state.clone();
+    tokio::spawn(async move {
+        loop {
+            if let Some(days) = uploads {
+                sweep(&state, StoredFiles::Upload, days).await;
+            }
+            if let Some(days) = results {
+                sweep(&state, StoredFiles::Results, days).await;
+            }
+            tokio::time::sleep(SWEEP_INTERVAL).await;
+        }
+    });
+}
+
+async fn sweep(state: &AppState, kind: StoredFiles, days: i32) {
+    let label = match kind {
+        StoredFiles::Upload => "uploads",
+        StoredFiles::Results => "results",
+    };
+    let pool = state.pool.as_ref();
+    let due = match crate::db::files_due_for_deletion(pool, kind, days, SWEEP_BATCH).await {
+        Ok(due) => due,
+        Err(e) => {
+            tracing::error!("Retention sweep of {} failed: {}", label, e);
+            return;
+        }
+    };
+
+    let mut removed = 0;
+    let mut freed = 0;
+    for (submission_id, file_path) in due {
+        let deleted = match kind {
+            StoredFiles::Upload => remove_file(Path::new(&file_path)),
+            StoredFiles::Results => remove_dir(&crate::storage::submission_results_dir(
+                &state.config.results_folder,
+                &submission_id,
+            )),
+        };
+        match deleted {
+            Ok(bytes) => {
+                state.storage_usage.release(bytes);
+                freed += bytes;
+                removed += 1;
+                if let Err(e) = crate::db::mark_files_deleted(pool, kind, &submission_id).await {
+                    tracing::error!(
+                        "Failed to record deleted {} of {}: {}",
+                        label,
+                        submission_id,
+                        e
+                    );
+                }
+                audit::record(
+                    state,
+                    "delete",
+                    Some(&submission_id),
+                    audit::SYSTEM,
+                    audit::SUCCESS,
+                    Some(label),
+                )
+                .await;
+            }
+            Err(e) => {
+                tracing::warn!("Failed to delete {} of {}: {}", label, submission_id, e);
+                let detail = format!("{}: {}", label, e);
+                audit::record(
+                    state,
+                    "delete",
+                    Some(&submission_id),
+                    audit::SYSTEM,
+                    audit::FAILURE,
+                    Some(&detail),
+                )
+                .await;
+            }
+        }
+    }
+    if removed > 0 {
+        tracing::info!(
+            "Retention: deleted {} of {} submissions older than {} days ({} bytes)",
+            label,
+            removed,
+            days,
+            freed
+        );
+    }
+}
+
+/// Deletes a file, returning its size; an already missing file counts as deleted.
+fn remove_file(path: &Path) -> std::io::Result<u64> {
+    let size = match std::fs::metadata(path) {
+        Ok(meta) => meta.len(),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+        Err(e) => return Err(e),
+    };
+    std::fs::remove_file(path)?;
+    Ok(size)
+}
+
+fn remove_dir(path: &Path) -> std::io::Result<u64> {
+    if !path.exists() {
+        return Ok(0);
+    }
+    let size = crate::storage::dir_size(path);
+    std::fs::remove_dir_all(path)?;
+    Ok(size)
+}
@@ -0,0 +1,55 @@
+// Authentication for the management routes (ADMIN_TOKEN).
+use axum::{
+    extract::{Request, State},
+    http::{HeaderMap, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+
+use crate::state::AppState;
+
+/// The token sent as `Authorization: Bearer <token>` or `X-Admin-Token`.
+fn request_admin_token(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(axum::http::header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .or_else(|| headers.get("X-Admin-Token").and_then(|v| v.to_str().ok()))
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+}
+
+/// Compares digests so the check takes the same time wherever a guess differs.
+fn token_matches(expected: &str, given: &str) -> bool {
+    let (a, b) = (Sha256::digest(expected), Sha256::digest(given));
+    a.iter()
+        .zip(b.iter())
+        .fold(0u8, |acc, (x, y)| acc | (x ^ y))
+        == 0
+}
+
+/// Refuses management requests without the configured `ADMIN_TOKEN` (401).
+/// Without a token the routes are only mounted on `ADMIN_BIND`.
+pub async fn require_admin(
+    State(state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if let Some(expected) = &state.config.admin_token {
+        let authorized =
+            request_admin_token(request.headers()).is_some_and(|t| token_matches(expected, t));
+        if !authorized {
+            return (
+                StatusCode::UNAUTHORIZED,
+                axum::Json(serde_json::json!({
+                    "status": "error",
+                    "message": "A valid admin token is required."
+                })),
+            )
+                .into_response();
+        }
+    }
+    next.run(request).await
+}
@@ -1,59 +1,451 @@
 use axum::{
-    extract::{Path, State},
+    extract::{ConnectInfo, Multipart, Path, Query, State},
+    http::{HeaderMap, StatusCode},
     response::IntoResponse,
 };
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use serde::Deserialize;
 use std::io::Write;
+use std::net::SocketAddr;
 use std::sync::Arc;
 
+use super::pages::{
+    author_token_matches, check_captcha, complete_status, ensure_certificate_image,
+    expired_response, may_view, pending_status, read_upload_form, request_author_token,
+    start_submission, submission_expired, write_certificate, AuthorTokenQuery, MAINTENANCE_MESSAGE,
+};
+use super::tenant::{in_tenant, request_tenant};
+use crate::audit;
+use crate::config::CertificateFormat;
 use crate::state::AppState;
 
-pub async fn download_file(
+#[derive(Deserialize)]
+pub struct SubmitQuery {
+    #[serde(default)]
+    sync: bool,
+}
+
+/// JSON counterpart of the upload form. A repeated `Idempotency-Key` header
+/// returns the submission created by the first request instead of starting
+/// another review run. With `?sync=true` small papers are reviewed inline and
+/// the completed results are returned directly.
+pub async fn submit(
     State(state): State<Arc<AppState>>,
-    Path(filename): Path<String>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    Query(query): Query<SubmitQuery>,
+    headers: HeaderMap,
+    mut multipart: Multipart,
 ) -> impl IntoResponse {
-    if filename.contains("..") || filename.is_empty() {
-        return axum::response::Redirect::to("/").into_response();
+    if state.maintenance.is_enabled() {
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            axum::Json(serde_json::json!({
+                "status": "error",
+                "message": MAINTENANCE_MESSAGE
+            })),
+        )
+            .into_response();
+    }
+    if let Some(message) = state
+        .config
+        .submission_window
+        .closed_message(chrono::Utc::now())
+    {
+        return (
+            StatusCode::FORBIDDEN,
+            axum::Json(serde_json::json!({
+                "status": "error",
+                "message": message
+            })),
+        )
+            .into_response();
     }
 
-    let results_path = state.config.results_folder.join(&filename);
-    if results_path.exists() {
-        match std::fs::read(&results_path) {
-            Ok(content) => {
-                let mime = mime_guess::from_path(&filename)
-                    .first_raw()
-                    .unwrap_or("application/octet-stream");
-                return axum::response::Response::builder()
-                    .header("Content-Type", mime)
-                    .header(
-                        "Content-Disposition",
-                        format!("attachment; filename=\"{}\"", filename),
+    let idempotency_key = headers
+        .get("Idempotency-Key")
+        .and_then(|v| v.to_str().ok())
+        .map(str::trim)
+        .filter(|k| !k.is_empty())
+        .map(str::to_string);
+    let ttl = state.config.idempotency_key_ttl_secs;
+    let tenant = request_tenant(&state, &headers).map(str::to_string);
+
+    if let Some(ref key) = idempotency_key {
+        match crate::db::find_idempotent_submission(
+            state.pool.as_ref(),
+            tenant.as_deref(),
+            key,
+            ttl,
+        )
+        .await
+        {
+            Ok(Some(submission_id)) => {
+                // The client may have lost the first response, token included,
+                // so the replay issues a fresh token in place of the old one
+                let author_token = crate::storage::generate_author_token();
+                let hash = crate::storage::hash_author_token(&author_token);
+                if let Err(e) =
+                    crate::db::rotate_author_token_hash(state.pool.as_ref(), &submission_id, &hash)
+                        .await
+                {
+                    tracing::error!("Failed to rotate author token for {}: {}", submission_id, e);
+                    return (
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        axum::Json(serde_json::json!({
+                            "status": "error",
+                            "message": "Database error."
+                        })),
                     )
-                    .body(axum::body::Body::from(content))
-                    .unwrap()
+                        .into_response();
+                }
+                state.status_cache.invalidate(&submission_id);
+                return axum::Json(serde_json::json!({
+                    "status": "ok",
+                    "submission_id": submission_id,
+                    "author_token": author_token,
+                    "results_url": format!("/results/{}?token={}", submission_id, author_token),
+                    "idempotent_replay": true
+                }))
+                .into_response();
+            }
+            Ok(None) => {}
+            Err(e) => {
+                tracing::error!("Idempotency key lookup failed: {}", e);
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    axum::Json(serde_json::json!({
+                        "status": "error",
+                        "message": "Database error."
+                    })),
+                )
                     .into_response();
             }
-            Err(_) => {}
+        }
+    }
+
+    let mut form = match read_upload_form(&mut multipart, state.config.max_upload_bytes).await {
+        Ok(form) => form,
+        Err(rejection) => return rejection.into_response(),
+    };
+    form.tenant = tenant.clone();
+    let actor = audit::actor(&state, &headers, addr);
+    if let Err(rejection) = check_captcha(&state, &form).await {
+        audit::record(
+            &state,
+            "upload",
+            None,
+            &actor,
+            audit::FAILURE,
+            Some(&rejection.message),
+        )
+        .await;
+        return rejection.into_response();
+    }
+    let created = match start_submission(&state, form, query.sync).await {
+        Ok(created) => created,
+        Err(rejection) => {
+            audit::record(
+                &state,
+                "upload",
+                None,
+                &actor,
+                audit::FAILURE,
+                Some(&rejection.message),
+            )
+            .await;
+            return rejection.into_response();
+        }
+    };
+    audit::record(
+        &state,
+        "upload",
+        Some(&created.submission_id),
+        &actor,
+        audit::SUCCESS,
+        None,
+    )
+    .await;
+    let submission_id = created.submission_id;
+    let author_token = created.author_token;
+
+    if let Some(ref key) = idempotency_key {
+        if let Err(e) = crate::db::save_idempotency_key(
+            state.pool.as_ref(),
+            tenant.as_deref(),
+            key,
+            &submission_id,
+            ttl,
+        )
+        .await
+        {
+            tracing::error!("Failed to save idempotency key: {}", e);
+        }
+    }
+
+    if query.sync {
+        let pool = state.pool.as_ref();
+        return match (
+            crate::db::get_submission(pool, &submission_id).await,
+            crate::db::get_review_results(pool, &submission_id).await,
+        ) {
+            (Ok(Some(submission)), Ok(results)) => {
+                let mut body = complete_status(&submission, &results);
+                body["author_token"] = serde_json::Value::from(author_token);
+                body["warnings"] = serde_json::Value::from(created.warnings);
+                axum::Json(body).into_response()
+            }
+            _ => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                axum::Json(serde_json::json!({
+                    "status": "error",
+                    "submission_id": submission_id,
+                    "message": "Failed to load results."
+                })),
+            )
+                .into_response(),
+        };
+    }
+
+    (
+        StatusCode::CREATED,
+        axum::Json(serde_json::json!({
+            "status": "ok",
+            "submission_id": submission_id,
+            "author_token": author_token,
+            "results_url": format!("/results/{}?token={}", submission_id, author_token),
+            "warnings": created.warnings,
+            "idempotent_replay": false
+        })),
+    )
+        .into_response()
+}
+
+pub async fn download_file(
+    State(state): State<Arc<AppState>>,
+    Path((submission_id, filename)): Path<(String, String)>,
+    Query(auth): Query<AuthorTokenQuery>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    if !is_safe_path_segment(&submission_id) || !is_safe_path_segment(&filename) {
+        return axum::response::Redirect::to("/").into_response();
+    }
+    let token = request_author_token(auth.token.as_deref(), &headers);
+    match crate::db::get_submission(state.pool.as_ref(), &submission_id).await {
+        Ok(Some(s)) if !in_tenant(&state, &headers, &s) => {
+            return StatusCode::NOT_FOUND.into_response()
+        }
+        Ok(Some(s)) if submission_expired(&s) => return expired_response(&s),
+        Ok(Some(s)) if may_view(&state, &s, token) => {}
+        // Without token gating, files remain downloadable by name
+        _ if !state.config.require_author_token_for_view => {}
+        _ => return axum::response::Redirect::to("/").into_response(),
+    }
+    if !crate::storage::download_allowed(&state.config.download_allowed_files, &filename) {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+
+    let results_path = crate::storage::resolve_result_file(
+        &state.config.results_folder,
+        &submission_id,
+        &filename,
+    );
+    if results_path.exists() {
+        if let Ok(content) = std::fs::read(&results_path) {
+            let mime = mime_guess::from_path(&filename)
+                .first_raw()
+                .unwrap_or("application/octet-stream");
+            return attachment_response(&headers, mime, &filename, content);
         }
     }
 
     axum::response::Redirect::to("/").into_response()
 }
 
+/// A single path component that cannot escape its parent directory.
+fn is_safe_path_segment(segment: &str) -> bool {
+    !segment.is_empty() && !segment.contains("..") && !segment.contains(['/', '\\'])
+}
+
+/// Builds a download response. Titles and filenames are user-controlled, so
+/// the Content-Disposition value is built by [`content_disposition`], and a
+/// header the builder still rejects yields a 500 rather than a panic.
+/// A single `Range` in the request is answered with 206 Partial Content (or
+/// 416 when it lies outside the body). HEAD requests reach here through the
+/// GET routes; axum drops the body and the explicit Content-Length remains.
+fn attachment_response(
+    request_headers: &HeaderMap,
+    content_type: &str,
+    download_name: &str,
+    body: Vec<u8>,
+) -> axum::response::Response {
+    let total = body.len();
+    let builder = axum::response::Response::builder()
+        .header("Content-Type", content_type)
+        .header("Content-Disposition", content_disposition(download_name))
+        .header("Accept-Ranges", "bytes");
+    let response = match requested_range(request_headers, total) {
+        None => builder
+            .header("Content-Length", total)
+            .body(axum::body::Body::from(body)),
+        Some(Ok((start, end))) => builder
+            .status(StatusCode::PARTIAL_CONTENT)
+            .header(
+                "Content-Range",
+                format!("bytes {}-{}/{}", start, end, total),
+            )
+            .header("Content-Length", end - start + 1)
+            .body(axum::body::Body::from(body[start..=end].to_vec())),
+        Some(Err(())) => builder
+            .status(StatusCode::RANGE_NOT_SATISFIABLE)
+            .header("Content-Range", format!("bytes */{}", total))
+            .body(axum::body::Body::empty()),
+    };
+    response.unwrap_or_else(|e| {
+        tracing::error!("Failed to build download response: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR.into_response()
+    })
+}
+
+/// The inclusive byte range asked for by a single-range `Range: bytes=`
+/// header on a body of `len` bytes. Absent, malformed and multi-range headers
+/// give `None` (serve everything); `Some(Err(()))` is unsatisfiable.
+fn requested_range(headers: &HeaderMap, len: usize) -> Option<Result<(usize, usize), ()>> {
+    let spec = headers
+        .get(axum::http::header::RANGE)?
+        .to_str()
+        .ok()?
+        .trim()
+        .strip_prefix("bytes=")?;
+    if spec.contains(',') {
+        return None;
+    }
+    let (start, end) = spec.split_once('-')?;
+    let (start, end) = (start.trim(), end.trim());
+    if start.is_empty() {
+        // Suffix range: the last `end` bytes
+        let suffix: usize = end.parse().ok()?;
+        if suffix == 0 || len == 0 {
+            return Some(Err(()));
+        }
+        return Some(Ok((len.saturating_sub(suffix), len - 1)));
+    }
+    let start: usize = start.parse().ok()?;
+    let end = if end.is_empty() {
+        len.saturating_sub(1)
+    } else {
+        end.parse::<usize>().ok()?.min(len.saturating_sub(1))
+    };
+    if start >= len || start > end {
+        return Some(Err(()));
+    }
+    Some(Ok((start, end)))
+}
+
+/// Characters allowed unencoded in an RFC 5987 `ext-value` (`attr-char`).
+const ATTR_CHAR_ENCODE: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'!')
+    .remove(b'#')
+    .remove(b'$')
+    .remove(b'&')
+    .remove(b'+')
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'^')
+    .remove(b'_')
+    .remove(b'`')
+    .remove(b'|')
+    .remove(b'~');
+
+/// RFC 6266 attachment header: an ASCII-only quoted `filename` fallback for
+/// old clients plus a UTF-8 percent-encoded `filename*` carrying the real name.
+fn content_disposition(name: &str) -> String {
+    let name = sanitize_download_name(name);
+    let fallback: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii() && c != '"' && c != '\\' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!(
+        "attachment; filename=\"{}\"; filename*=UTF-8''{}",
+        fallback,
+        utf8_percent_encode(&name, ATTR_CHAR_ENCODE)
+    )
+}
+
+fn sanitize_download_name(name: &str) -> String {
+    let cleaned: String = name.chars().filter(|c| !c.is_control()).collect();
+    if cleaned.trim().is_empty() {
+        "download".to_string()
+    } else {
+        cleaned
+    }
+}
+
+pub async fn serve_static(Path(path): Path<String>) -> impl IntoResponse {
+    if path.contains("..") || path.is_empty() {
+        return axum::http::StatusCode::NOT_FOUND.into_response();
+    }
+
+    match std::fs::read(std::path::Path::new("static").join(&path)) {
+        Ok(content) => {
+            let mime = mime_guess::from_path(&path)
+                .first_raw()
+                .unwrap_or("application/octet-stream");
+            ([(axum::http::header::CONTENT_TYPE, mime)], content).into_response()
+        }
+        Err(_) => axum::http::StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+#[derive(Deserialize)]
+pub struct CertificateQuery {
+    token: Option<String>,
+    /// `pdf` or `png`; defaults to `CERTIFICATE_FORMAT`.
+    format: Option<String>,
+}
+
 pub async fn download_certificate(
     State(state): State<Arc<AppState>>,
     Path(submission_id): Path<String>,
+    Query(query): Query<CertificateQuery>,
+    headers: HeaderMap,
 ) -> impl IntoResponse {
+    let format = match query.format.as_deref() {
+        None | Some("") => state.config.certificate_format,
+        Some("pdf") => CertificateFormat::Pdf,
+        Some("png") => CertificateFormat::Png,
+        Some(_) => return StatusCode::BAD_REQUEST.into_response(),
+    };
     let submission = match crate::db::get_submission(state.pool.as_ref(), &submission_id).await {
-        Ok(Some(s)) => s,
+        Ok(Some(s)) if in_tenant(&state, &headers, &s) => s,
         _ => return axum::response::Redirect::to("/").into_response(),
     };
+    if !may_view(
+        &state,
+        &submission,
+        request_author_token(query.token.as_deref(), &headers),
+    ) {
+        return axum::response::Redirect::to("/").into_response();
+    }
+    if submission_expired(&submission) {
+        return expired_response(&submission);
+    }
 
     let cert_filename = match submission.certificate_filename {
         Some(f) => f,
         None => return axum::response::Redirect::to("/").into_response(),
     };
 
-    let cert_path = state.config.results_folder.join(&cert_filename);
+    let cert_path = crate::storage::resolve_result_file(
+        &state.config.results_folder,
+        &submission.submission_id,
+        &cert_filename,
+    );
     if !cert_path.exists() {
         return axum::response::Redirect::to("/").into_response();
     }
@@ -66,45 +458,260 @@ pub async fn download_certificate(
     let paper_title = submission
         .paper_title
         .unwrap_or_else(|| "Research_Paper".to_string());
+
+    if format == CertificateFormat::Png {
+        let image = match ensure_certificate_image(&state, &cert_path).await {
+            Ok(image_path) => std::fs::read(image_path),
+            Err(e) => {
+                tracing::warn!("Certificate image unavailable for {}: {}", submission_id, e);
+                return StatusCode::SERVICE_UNAVAILABLE.into_response();
+            }
+        };
+        return match image {
+            Ok(image) => {
+                let download_name = format!("{}_Certificate.png", paper_title.replace(' ', "_"));
+                attachment_response(&headers, "image/png", &download_name, image)
+            }
+            Err(_) => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+        };
+    }
+
     let download_name = format!("{}_Certificate.pdf", paper_title.replace(' ', "_"));
+    attachment_response(&headers, "application/pdf", &download_name, content)
+}
 
-    axum::response::Response::builder()
-        .header("Content-Type", "application/pdf")
-        .header(
-            "Content-Disposition",
-            format!("attachment; filename=\"{}\"", download_name),
-        )
-        .body(axum::body::Body::from(content))
-        .unwrap()
-        .into_response()
+/// The originally uploaded PDF, for its author. Needs the author token even
+/// when results are public; the path comes from the database, never the URL.
+pub async fn download_paper(
+    State(state): State<Arc<AppState>>,
+    Path(submission_id): Path<String>,
+    Query(auth): Query<AuthorTokenQuery>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    let submission = match crate::db::get_submission(state.pool.as_ref(), &submission_id).await {
+        Ok(Some(s)) if in_tenant(&state, &headers, &s) => s,
+        Ok(_) => return StatusCode::NOT_FOUND.into_response(),
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+    };
+    let token = request_author_token(auth.token.as_deref(), &headers);
+    if submission.author_token_hash.is_none() || !author_token_matches(&submission, token) {
+        return StatusCode::FORBIDDEN.into_response();
+    }
+    if submission_expired(&submission) {
+        return expired_response(&submission);
+    }
+    paper_response(&headers, &submission).await
 }
 
-pub async fn download_all(
+/// Management counterpart of [`download_paper`]: no author token, but only
+/// reachable behind [`require_admin`](super::require_admin) or on `ADMIN_BIND`.
+pub async fn admin_download_paper(
     State(state): State<Arc<AppState>>,
     Path(submission_id): Path<String>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    match crate::db::get_submission(state.pool.as_ref(), &submission_id).await {
+        Ok(Some(submission)) => paper_response(&headers, &submission).await,
+        Ok(None) => StatusCode::NOT_FOUND.into_response(),
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+    }
+}
+
+async fn paper_response(
+    headers: &HeaderMap,
+    submission: &crate::db::Submission,
+) -> axum::response::Response {
+    match tokio::fs::read(&submission.file_path).await {
+        Ok(content) => {
+            let name = submission.filename.as_deref().unwrap_or("paper.pdf");
+            attachment_response(headers, "application/pdf", name, content)
+        }
+        Err(_) => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+const PREVIEW_PLACEHOLDER: &str = "/static/images/preview-placeholder.svg";
+
+/// PNG of the submitted paper's first page, rendered on first request and
+/// cached with the submission's results. Falls back to a placeholder image
+/// when the paper cannot be rendered.
+pub async fn paper_preview(
+    State(state): State<Arc<AppState>>,
+    Path(submission_id): Path<String>,
+    Query(auth): Query<AuthorTokenQuery>,
+    headers: HeaderMap,
 ) -> impl IntoResponse {
     let submission = match crate::db::get_submission(state.pool.as_ref(), &submission_id).await {
-        Ok(Some(s)) => s,
-        _ => return axum::response::Redirect::to("/").into_response(),
+        Ok(Some(s)) if in_tenant(&state, &headers, &s) => s,
+        _ => return StatusCode::NOT_FOUND.into_response(),
     };
+    if !may_view(
+        &state,
+        &submission,
+        request_author_token(auth.token.as_deref(), &headers),
+    ) {
+        return StatusCode::FORBIDDEN.into_response();
+    }
+    if submission_expired(&submission) {
+        return StatusCode::GONE.into_response();
+    }
 
-    let mut zip_data = Vec::new();
-    {
-        let mut zip = zip::ZipWriter::new(std::io::Cursor::new(&mut zip_data));
-        let options = zip::write::FileOptions::default().unix_permissions(0o644);
-
-        if let Some(ref cert_filename) = submission.certificate_filename {
-            let cert_path = state.config.results_folder.join(cert_filename);
-            if cert_path.exists() {
-                if let Ok(content) = std::fs::read(&cert_path) {
-                    let _ = zip.start_file(cert_filename, options);
-                    let _ = zip.write_all(&content);
+    let results_dir =
+        crate::storage::submission_results_dir(&state.config.results_folder, &submission_id);
+    let preview_path = results_dir.join("preview.png");
+    if !preview_path.exists() {
+        let rendered = match crate::storage::create_dir(&results_dir, state.config.dir_mode) {
+            Ok(()) => {
+                crate::pdf::render_first_page(
+                    &state.config.pdftoppm_path,
+                    std::path::Path::new(&submission.file_path),
+                    crate::pdf::PREVIEW_WIDTH,
+                    &state.config.temp_folder,
+                    &preview_path,
+                    state.config.file_mode,
+                )
+                .await
+            }
+            Err(e) => Err(e.to_string()),
+        };
+        match rendered {
+            Ok(()) => {
+                if let Ok(meta) = std::fs::metadata(&preview_path) {
+                    state.storage_usage.add(meta.len());
                 }
             }
+            Err(e) => {
+                tracing::warn!("Preview rendering failed for {}: {}", submission_id, e);
+                return axum::response::Redirect::to(PREVIEW_PLACEHOLDER).into_response();
+            }
         }
+    }
 
-        let _ = zip.finish();
+    match std::fs::read(&preview_path) {
+        Ok(content) => ([(axum::http::header::CONTENT_TYPE, "image/png")], content).into_response(),
+        Err(_) => axum::response::Redirect::to(PREVIEW_PLACEHOLDER).into_response(),
     }
+}
+
+pub async fn verify_certificate(
+    State(state): State<Arc<AppState>>,
+    Path(submission_id): Path<String>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    let submission = match crate::db::get_submission(state.pool.as_ref(), &submission_id).await {
+        Ok(Some(s)) if in_tenant(&state, &headers, &s) => s,
+        Ok(_) => {
+            return axum::Json(serde_json::json!({
+                "valid": false,
+                "status": "not_found",
+                "message": "No certificate with this ID."
+            }))
+            .into_response()
+        }
+        Err(_) => {
+            return axum::Json(serde_json::json!({
+                "valid": false,
+                "status": "error",
+                "message": "Database error."
+            }))
+            .into_response()
+        }
+    };
+
+    let issued_at = match (
+        &submission.certificate_filename,
+        submission.certificate_issued_at,
+    ) {
+        (Some(_), Some(issued_at)) => issued_at,
+        _ => {
+            return axum::Json(serde_json::json!({
+                "valid": false,
+                "status": "no_certificate",
+                "message": "No certificate was issued for this submission."
+            }))
+            .into_response()
+        }
+    };
+
+    let expires_at = state
+        .config
+        .certificate_validity_days
+        .map(|days| issued_at + chrono::Duration::days(days));
+    let expired = expires_at.is_some_and(|e| chrono::Utc::now() > e);
+
+    axum::Json(serde_json::json!({
+        "valid": !expired,
+        "status": if expired { "expired" } else { "valid" },
+        "submission_id": submission.submission_id,
+        "paper_title": submission.paper_title,
+        "certificate_kind": submission.certificate_kind,
+        "issued_at": issued_at,
+        "expires_at": expires_at
+    }))
+    .into_response()
+}
+
+pub async fn download_all(
+    State(state): State<Arc<AppState>>,
+    Path(submission_id): Path<String>,
+    Query(auth): Query<AuthorTokenQuery>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    let submission = match crate::db::get_submission(state.pool.as_ref(), &submission_id).await {
+        Ok(Some(s)) if in_tenant(&state, &headers, &s) => s,
+        _ => return axum::response::Redirect::to("/").into_response(),
+    };
+    if !may_view(
+        &state,
+        &submission,
+        request_author_token(auth.token.as_deref(), &headers),
+    ) {
+        return axum::response::Redirect::to("/").into_response();
+    }
+    if submission_expired(&submission) {
+        return expired_response(&submission);
+    }
+
+    // Archive members in output order
+    let mut members: Vec<(String, std::path::PathBuf)> = Vec::new();
+    if let Some(ref cert_filename) = submission.certificate_filename {
+        let cert_path = crate::storage::resolve_result_file(
+            &state.config.results_folder,
+            &submission.submission_id,
+            cert_filename,
+        );
+        members.push((cert_filename.clone(), cert_path));
+    }
+
+    let permits = Arc::new(tokio::sync::Semaphore::new(
+        state.config.download_all_read_concurrency,
+    ));
+    let mut reads = tokio::task::JoinSet::new();
+    for (index, (_, path)) in members.iter().enumerate() {
+        let path = path.clone();
+        let permits = permits.clone();
+        reads.spawn(async move {
+            let _permit = permits.acquire_owned().await;
+            (index, tokio::fs::read(&path).await.ok())
+        });
+    }
+    let mut contents: Vec<Option<Vec<u8>>> = vec![None; members.len()];
+    while let Some(read) = reads.join_next().await {
+        if let Ok((index, content)) = read {
+            contents[index] = content;
+        }
+    }
+    // Missing or unreadable files are left out of the archive
+    let files: Vec<(String, Vec<u8>)> = members
+        .into_iter()
+        .zip(contents)
+        .filter_map(|((name, _), content)| content.map(|c| (name, c)))
+        .collect();
+
+    let zip_data = match tokio::task::spawn_blocking(move || build_zip(&files)).await {
+        Ok(zip_data) => zip_data,
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+    };
 
     let download_name = format!(
         "{}_All_Reviews.zip",
@@ -114,13 +721,708 @@ pub async fn download_all(
             .replace(' ', "_")
     );
 
-    axum::response::Response::builder()
-        .header("Content-Type", "application/zip")
-        .header(
-            "Content-Disposition",
-            format!("attachment; filename=\"{}\"", download_name),
+    attachment_response(&headers, "application/zip", &download_name, zip_data)
+}
+
+/// What a client can download for a submission right now, with the same
+/// checks as the download routes: the certificate and review files must
+/// exist on disk, and the original paper is listed only for a caller
+/// holding the author token. A submission with nothing yet gets `[]`.
+pub async fn list_artifacts(
+    State(state): State<Arc<AppState>>,
+    Path(submission_id): Path<String>,
+    Query(auth): Query<AuthorTokenQuery>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    let submission = match crate::db::get_submission(state.pool.as_ref(), &submission_id).await {
+        Ok(Some(s)) if in_tenant(&state, &headers, &s) => s,
+        Ok(_) => return StatusCode::NOT_FOUND.into_response(),
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+    };
+    let token = request_author_token(auth.token.as_deref(), &headers);
+    if !may_view(&state, &submission, token) {
+        return StatusCode::FORBIDDEN.into_response();
+    }
+    if submission_expired(&submission) {
+        return expired_response(&submission);
+    }
+    let owner = submission.author_token_hash.is_some() && author_token_matches(&submission, token);
+    // Only a valid token is echoed back into the URLs
+    let token_query = match token {
+        Some(t) if owner => format!("?token={}", t),
+        _ => String::new(),
+    };
+    let result_file = |name: &str| {
+        crate::storage::resolve_result_file(&state.config.results_folder, &submission_id, name)
+    };
+
+    let mut artifacts = Vec::new();
+    let certificate = submission
+        .certificate_filename
+        .as_deref()
+        .filter(|name| result_file(name).exists());
+    if let Some(name) = certificate {
+        artifacts.push(serde_json::json!({
+            "type": "certificate",
+            "filename": name,
+            "content_type": "application/pdf",
+            "url": format!("/download_certificate/{}{}", submission_id, token_query),
+        }));
+    }
+
+    let reviews = crate::db::list_review_results(
+        state.pool.as_ref(),
+        &submission_id,
+        None,
+        false,
+        i64::MAX,
+        0,
+    )
+    .await
+    .unwrap_or_default();
+    for review in reviews {
+        let Some(name) = review
+            .file_url
+            .as_deref()
+            .filter(|n| is_safe_path_segment(n))
+        else {
+            continue;
+        };
+        if crate::storage::download_allowed(&state.config.download_allowed_files, name)
+            && result_file(name).exists()
+        {
+            artifacts.push(serde_json::json!({
+                "type": "review",
+                "reviewer": review.reviewer_name,
+                "filename": name,
+                "content_type": mime_guess::from_path(name)
+                    .first_raw()
+                    .unwrap_or("application/octet-stream"),
+                "url": format!("/download/{}/{}{}", submission_id, name, token_query),
+            }));
+        }
+    }
+
+    if owner && std::path::Path::new(&submission.file_path).exists() {
+        artifacts.push(serde_json::json!({
+            "type": "paper",
+            "filename": submission.filename.as_deref().unwrap_or("paper.pdf"),
+            "content_type": "application/pdf",
+            "url": format!("/download_paper/{}{}", submission_id, token_query),
+        }));
+    }
+
+    // The archive holds the certificate, so it is only worth offering with one
+    if certificate.is_some() {
+        artifacts.push(serde_json::json!({
+            "type": "archive",
+            "content_type": "application/zip",
+            "url": format!("/download_all/{}{}", submission_id, token_query),
+        }));
+    }
+
+    axum::Json(serde_json::json!({
+        "submission_id": submission_id,
+        "artifacts": artifacts
+    }))
+    .into_response()
+}
+
+/// Writes `files` into an in-memory zip archive, in order.
+fn build_zip(files: &[(String, Vec<u8>)]) -> Vec<u8> {
+    let mut zip_data = Vec::new();
+    {
+        let mut zip = zip::ZipWriter::new(std::io::Cursor::new(&mut zip_data));
+        let options = zip::write::SimpleFileOptions::default().unix_permissions(0o644);
+
+        for (name, content) in files {
+            let _ = zip.start_file(name.as_str(), options);
+            let _ = zip.write_all(content);
+        }
+
+        let _ = zip.finish();
+    }
+    zip_data
+}
+
+/// Most submission IDs accepted by one batch status request.
+const MAX_BATCH_STATUS_IDS: usize = 100;
+
+/// Status of several submissions at once. The body is a JSON array of
+/// submission IDs; the response maps each ID to a compact status
+/// (`processing`, `complete`, `expired`, `forbidden` or `not_found`).
+/// Submissions that need an author token to view are reported as
+/// `forbidden`; use `/api/status/<id>` with the token for those.
+pub async fn batch_status(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    axum::Json(submission_ids): axum::Json<Vec<String>>,
+) -> impl IntoResponse {
+    if submission_ids.len() > MAX_BATCH_STATUS_IDS {
+        return (
+            StatusCode::BAD_REQUEST,
+            axum::Json(serde_json::json!({
+                "status": "error",
+                "message": format!("At most {} submission IDs per request.", MAX_BATCH_STATUS_IDS)
+            })),
         )
-        .body(axum::body::Body::from(zip_data))
-        .unwrap()
-        .into_response()
+            .into_response();
+    }
+
+    let rows = match crate::db::get_submission_progress(state.pool.as_ref(), &submission_ids).await
+    {
+        Ok(rows) => rows,
+        Err(_) => {
+            return axum::Json(serde_json::json!({
+                "status": "error",
+                "message": "Database error."
+            }))
+            .into_response()
+        }
+    };
+
+    let now = chrono::Utc::now();
+    let tenant = request_tenant(&state, &headers);
+    let mut statuses = serde_json::Map::new();
+    for id in &submission_ids {
+        let row = rows
+            .iter()
+            .find(|r| r.submission_id == *id)
+            .filter(|r| state.config.tenancy.is_none() || r.tenant_id.as_deref() == tenant);
+        let status = match row {
+            None => serde_json::json!({ "status": "not_found" }),
+            Some(r)
+                if state.config.require_author_token_for_view && r.author_token_hash.is_some() =>
+            {
+                serde_json::json!({ "status": "forbidden" })
+            }
+            Some(r) if r.expires_at.is_some_and(|at| at <= now) => {
+                serde_json::json!({ "status": "expired" })
+            }
+            Some(r) if !r.processing_complete => serde_json::json!({
+                "status": pending_status(r.completed_reviews, r.review_started),
+                "completed": r.completed_reviews,
+                "total": state.config.reviewer_count
+            }),
+            Some(r) => serde_json::json!({
+                "status": "complete",
+                "all_accepted": r.all_accepted,
+                "certificate_filename": r.certificate_filename,
+                "certificate_kind": r.certificate_kind
+            }),
+        };
+        statuses.insert(id.clone(), status);
+    }
+
+    axum::Json(serde_json::json!({
+        "status": "ok",
+        "submissions": statuses
+    }))
+    .into_response()
+}
+
+#[derive(Deserialize)]
+pub struct TitleUpdate {
+    paper_title: String,
+    /// Reissue an existing certificate with the new title.
+    #[serde(default = "default_true")]
+    regenerate_certificate: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Corrects the title of a reviewed submission without re-reviewing it.
+/// Needs the author token; an existing certificate is reissued with the new
+/// title and its original issue date unless `regenerate_certificate` is false.
+pub async fn update_submission(
+    State(state): State<Arc<AppState>>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    Path(submission_id): Path<String>,
+    Query(query): Query<AuthorTokenQuery>,
+    headers: HeaderMap,
+    axum::Json(update): axum::Json<TitleUpdate>,
+) -> axum::response::Response {
+    let error = |status: StatusCode, message: String| {
+        (
+            status,
+            axum::Json(serde_json::json!({
+                "status": "error",
+                "message": message
+            })),
+        )
+            .into_response()
+    };
+
+    let submission = match crate::db::get_submission(state.pool.as_ref(), &submission_id).await {
+        Ok(Some(s)) if in_tenant(&state, &headers, &s) => s,
+        _ => return error(StatusCode::NOT_FOUND, "Submission not found.".to_string()),
+    };
+    if !author_token_matches(
+        &submission,
+        request_author_token(query.token.as_deref(), &headers),
+    ) {
+        return error(
+            StatusCode::FORBIDDEN,
+            "A valid author token is required.".to_string(),
+        );
+    }
+    if submission_expired(&submission) {
+        return expired_response(&submission);
+    }
+    // The running review's certificate would still carry the old title
+    if !submission.processing_complete {
+        return error(
+            StatusCode::CONFLICT,
+            "The title can be changed once the review has finished.".to_string(),
+        );
+    }
+
+    let paper_title = update.paper_title.trim();
+    if paper_title.is_empty() {
+        return error(
+            StatusCode::BAD_REQUEST,
+            "A paper title is required.".to_string(),
+        );
+    }
+    if paper_title.chars().count() > state.config.title_max_chars {
+        return error(
+            StatusCode::BAD_REQUEST,
+            format!(
+                "The paper title must be at most {} characters.",
+                state.config.title_max_chars
+            ),
+        );
+    }
+
+    let Some(_lock) = state.submission_locks.try_lock(&submission_id) else {
+        return error(
+            StatusCode::CONFLICT,
+            "This submission is already being updated; try again shortly.".to_string(),
+        );
+    };
+    let actor = audit::actor(&state, &headers, addr);
+    let old_title = submission.paper_title.as_deref().unwrap_or_default();
+    if let Err(e) =
+        crate::db::update_paper_title(state.pool.as_ref(), &submission_id, paper_title).await
+    {
+        tracing::error!("Failed to update title of {}: {}", submission_id, e);
+        audit::record(
+            &state,
+            "update_title",
+            Some(&submission_id),
+            &actor,
+            audit::FAILURE,
+            None,
+        )
+        .await;
+        return error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Database error.".to_string(),
+        );
+    }
+    state.status_cache.invalidate(&submission_id);
+
+    // Certificates removed by the retention sweep stay removed
+    let certificate = submission
+        .certificate_kind
+        .as_deref()
+        .and_then(crate::pdf::CertificateKind::parse)
+        .zip(submission.certificate_issued_at)
+        .filter(|_| update.regenerate_certificate && submission.results_deleted_at.is_none());
+    let certificate_regenerated = match certificate {
+        Some((kind, issued_at)) => {
+            match write_certificate(&state, &submission_id, paper_title, kind, issued_at).await {
+                Ok(_) => true,
+                Err(e) => {
+                    tracing::error!(
+                        "Certificate regeneration failed for {}: {}",
+                        submission_id,
+                        e
+                    );
+                    false
+                }
+            }
+        }
+        None => false,
+    };
+
+    let detail = format!(
+        "{:?} -> {:?}{}",
+        old_title,
+        paper_title,
+        if certificate_regenerated {
+            "; certificate regenerated"
+        } else {
+            ""
+        }
+    );
+    audit::record(
+        &state,
+        "update_title",
+        Some(&submission_id),
+        &actor,
+        audit::SUCCESS,
+        Some(&detail),
+    )
+    .await;
+
+    axum::Json(serde_json::json!({
+        "status": "ok",
+        "submission_id": submission_id,
+        "paper_title": paper_title,
+        "certificate_regenerated": certificate_regenerated
+    }))
+    .into_response()
+}
+
+#[derive(Deserialize)]
+pub struct ListQuery {
+    category: Option<String>,
+    tenant: Option<String>,
+}
+
+pub async fn list_submissions(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<ListQuery>,
+) -> impl IntoResponse {
+    let category = query.category.as_deref().filter(|c| !c.is_empty());
+    let tenant = query.tenant.as_deref().filter(|t| !t.is_empty());
+    match crate::db::list_submissions(state.pool.as_ref(), category, tenant).await {
+        Ok(submissions) => axum::Json(serde_json::json!({
+            "status": "ok",
+            "submissions": submissions
+        }))
+        .into_response(),
+        Err(_) => axum::Json(serde_json::json!({
+            "status": "error",
+            "message": "Database error."
+        }))
+        .into_response(),
+    }
+}
+
+const MAX_REVIEWS_PAGE: i64 = 100;
+
+#[derive(Deserialize)]
+pub struct ReviewListQuery {
+    decision: Option<String>,
+    /// Also list reviews superseded by a retry.
+    #[serde(default)]
+    history: bool,
+    limit: Option<i64>,
+    offset: Option<i64>,
+}
+
+/// Management listing of a submission's reviews in reviewer order, e.g.
+/// `?decision=ERROR` for the failed ones or `?history=true` to include
+/// reviews replaced by retries.
+pub async fn list_reviews(
+    State(state): State<Arc<AppState>>,
+    Path(submission_id): Path<String>,
+    Query(query): Query<ReviewListQuery>,
+) -> impl IntoResponse {
+    let decision = query
+        .decision
+        .as_deref()
+        .filter(|d| !d.is_empty())
+        .map(str::to_uppercase);
+    let limit = query
+        .limit
+        .unwrap_or(MAX_REVIEWS_PAGE)
+        .clamp(1, MAX_REVIEWS_PAGE);
+    let offset = query.offset.unwrap_or(0).max(0);
+
+    match crate::db::list_review_results(
+        state.pool.as_ref(),
+        &submission_id,
+        decision.as_deref(),
+        query.history,
+        limit,
+        offset,
+    )
+    .await
+    {
+        Ok(reviews) => axum::Json(serde_json::json!({
+            "status": "ok",
+            "reviews": reviews,
+            "limit": limit,
+            "offset": offset
+        }))
+        .into_response(),
+        Err(_) => axum::Json(serde_json::json!({
+            "status": "error",
+            "message": "Database error."
+        }))
+        .into_response(),
+    }
+}
+
+const MAX_AUDIT_PAGE: i64 = 500;
+
+#[derive(Deserialize)]
+pub struct AuditQuery {
+    action: Option<String>,
+    submission_id: Option<String>,
+    actor: Option<String>,
+    /// RFC 3339 timestamp; only entries at or after it.
+    since: Option<chrono::DateTime<chrono::Utc>>,
+    limit: Option<i64>,
+    offset: Option<i64>,
+}
+
+/// Management listing of the audit log, newest first, e.g.
+/// `?action=retry&submission_id=...`. Entries hold client addresses, so
+/// this is only served behind `ADMIN_TOKEN` or on `ADMIN_BIND`.
+pub async fn list_audit_log(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<AuditQuery>,
+) -> impl IntoResponse {
+    let filter = crate::db::AuditFilter {
+        action: query.action.as_deref().filter(|a| !a.is_empty()),
+        submission_id: query.submission_id.as_deref().filter(|s| !s.is_empty()),
+        actor: query.actor.as_deref().filter(|a| !a.is_empty()),
+        since: query.since,
+    };
+    let limit = query.limit.unwrap_or(100).clamp(1, MAX_AUDIT_PAGE);
+    let offset = query.offset.unwrap_or(0).max(0);
+
+    match crate::db::list_audit_entries(state.pool.as_ref(), &filter, limit, offset).await {
+        Ok(entries) => axum::Json(serde_json::json!({
+            "status": "ok",
+            "entries": entries,
+            "limit": limit,
+            "offset": offset
+        }))
+        .into_response(),
+        Err(_) => axum::Json(serde_json::json!({
+            "status": "error",
+            "message": "Database error."
+        }))
+        .into_response(),
+    }
+}
+
+/// One reviewer's review of a submission.
+pub async fn get_review(
+    State(state): State<Arc<AppState>>,
+    Path((submission_id, reviewer_name)): Path<(String, String)>,
+) -> impl IntoResponse {
+    match crate::db::get_review_result(state.pool.as_ref(), &submission_id, &reviewer_name).await {
+        Ok(Some(review)) => axum::Json(serde_json::json!({
+            "status": "ok",
+            "review": {
+                "reviewer_name": review.reviewer_name,
+                "decision": review.decision,
+                "summary": review.summary,
+                "full_review": review.full_review,
+                "model_used": review.model_used,
+                "model_sequence": review.model_sequence,
+                "weighted_score": review.weighted_score,
+                "confidence": review.confidence,
+                "created_at": review.created_at
+            }
+        }))
+        .into_response(),
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            axum::Json(serde_json::json!({
+                "status": "not_found",
+                "message": "Review not found."
+            })),
+        )
+            .into_response(),
+        Err(_) => axum::Json(serde_json::json!({
+            "status": "error",
+            "message": "Database error."
+        }))
+        .into_response(),
+    }
+}
+
+/// Rewrites the short summary of a reviewer's current review from its stored
+/// full text with `SUMMARY_MODEL`, leaving the review and decision alone.
+/// Each call is a paid request, so this is a management route (`ADMIN_TOKEN`
+/// or `ADMIN_BIND`) and runs one at a time per submission.
+pub async fn regenerate_review_summary(
+    State(state): State<Arc<AppState>>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    Path((submission_id, reviewer_name)): Path<(String, String)>,
+    headers: HeaderMap,
+) -> axum::response::Response {
+    let error = |status: StatusCode, message: &str| {
+        (
+            status,
+            axum::Json(serde_json::json!({
+                "status": "error",
+                "message": message
+            })),
+        )
+            .into_response()
+    };
+
+    let pool = state.pool.as_ref();
+    let review = match crate::db::get_review_result(pool, &submission_id, &reviewer_name).await {
+        Ok(Some(review)) => review,
+        Ok(None) => return error(StatusCode::NOT_FOUND, "Review not found."),
+        Err(_) => return error(StatusCode::INTERNAL_SERVER_ERROR, "Database error."),
+    };
+    let Some(full_review) = review
+        .full_review
+        .as_deref()
+        .filter(|r| !r.trim().is_empty())
+    else {
+        return error(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "The review has no text to summarize.",
+        );
+    };
+
+    let Some(_lock) = state.submission_locks.try_lock(&submission_id) else {
+        return error(
+            StatusCode::CONFLICT,
+            "This submission is already being updated; try again shortly.",
+        );
+    };
+    let actor = audit::actor(&state, &headers, addr);
+    let mut agent = crate::agents::ClaudeAgent::new(&state.config, state.http.clone());
+    let result = agent.summarize_review(full_review).await;
+    if state.config.store_token_usage {
+        let usage = agent.token_usage();
+        if let Err(e) = crate::db::add_submission_token_usage(
+            pool,
+            &submission_id,
+            usage.input_tokens,
+            usage.output_tokens,
+        )
+        .await
+        {
+            tracing::warn!("Failed to record token usage for {}: {}", submission_id, e);
+        }
+    }
+    let summary = match result {
+        Ok(summary) => summary,
+        Err(e) => {
+            tracing::error!("Summary regeneration failed for {}: {}", submission_id, e);
+            let detail = format!("{}: {}", reviewer_name, e);
+            audit::record(
+                &state,
+                "regenerate_summary",
+                Some(&submission_id),
+                &actor,
+                audit::FAILURE,
+                Some(&detail),
+            )
+            .await;
+            return error(
+                StatusCode::BAD_GATEWAY,
+                "The summary could not be generated.",
+            );
+        }
+    };
+    if crate::db::update_review_summary(pool, review.id, &summary)
+        .await
+        .is_err()
+    {
+        return error(StatusCode::INTERNAL_SERVER_ERROR, "Database error.");
+    }
+    state.status_cache.invalidate(&submission_id);
+    audit::record(
+        &state,
+        "regenerate_summary",
+        Some(&submission_id),
+        &actor,
+        audit::SUCCESS,
+        Some(&reviewer_name),
+    )
+    .await;
+
+    axum::Json(serde_json::json!({
+        "status": "ok",
+        "reviewer_name": reviewer_name,
+        "summary": summary,
+        "model": state.config.summary_model
+    }))
+    .into_response()
+}
+
+#[derive(Deserialize)]
+pub struct MaintenanceUpdate {
+    enabled: bool,
+}
+
+/// The review instructions in effect: the assembled system prompt, the
+/// scored criteria with weights and the decision thresholds.
+pub async fn review_prompt(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    axum::Json(serde_json::json!({
+        "status": "ok",
+        "prompt": crate::agents::active_system_prompt(&state.config),
+        "criteria": state.config.review_criteria,
+        "thresholds": state.config.decision_thresholds,
+        "reviewers": state.config.reviewer_labels(None),
+        "models": crate::agents::CLAUDE_MODELS
+    }))
+}
+
+pub async fn maintenance_status(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    axum::Json(serde_json::json!({
+        "status": "ok",
+        "maintenance": state.maintenance.is_enabled()
+    }))
+}
+
+/// Turns maintenance mode on or off without a restart. Management route:
+/// requires `ADMIN_TOKEN` or the `ADMIN_BIND` listener.
+pub async fn set_maintenance(
+    State(state): State<Arc<AppState>>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    axum::Json(update): axum::Json<MaintenanceUpdate>,
+) -> impl IntoResponse {
+    state.maintenance.set(update.enabled);
+    let detail = if update.enabled {
+        "enabled"
+    } else {
+        "disabled"
+    };
+    let actor = audit::actor(&state, &headers, addr);
+    audit::record(
+        &state,
+        "maintenance",
+        None,
+        &actor,
+        audit::SUCCESS,
+        Some(detail),
+    )
+    .await;
+    axum::Json(serde_json::json!({
+        "status": "ok",
+        "maintenance": state.maintenance.is_enabled()
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hostile_filename_yields_valid_content_disposition() {
+        let header = content_disposition("r\u{e9}sum\u{e9} \"final\"\r\nX-Evil: 1.pdf");
+        assert!(axum::http::HeaderValue::from_str(&header).is_ok());
+        assert!(!header.contains('\r') && !header.contains('\n'));
+
+        let (fallback, encoded) = header
+            .strip_prefix("attachment; filename=\"")
+            .and_then(|rest| rest.split_once("\"; filename*=UTF-8''"))
+            .expect("both filename parameters present");
+        assert_eq!(fallback, "r_sum_ _final_X-Evil: 1.pdf");
+        assert_eq!(encoded, "r%C3%A9sum%C3%A9%20%22final%22X-Evil%3A%201.pdf");
+        assert_eq!(
+            percent_encoding::percent_decode_str(encoded)
+                .decode_utf8()
+                .unwrap(),
+            "r\u{e9}sum\u{e9} \"final\"X-Evil: 1.pdf"
+        );
+    }
 }
@@ -0,0 +1,72 @@
+// Server-side check of the upload CAPTCHA token (Cloudflare Turnstile or
+// hCaptcha), so bots cannot start costly reviews on public deployments.
+use serde::Deserialize;
+
+use crate::config::{Captcha, CaptchaProvider};
+
+const TURNSTILE_VERIFY_URL: &str = "https://challenges.cloudflare.com/turnstile/v0/siteverify";
+const HCAPTCHA_VERIFY_URL: &str = "https://api.hcaptcha.com/siteverify";
+
+/// Form fields the providers' widgets add; `captcha_token` is for API clients.
+pub(crate) const CAPTCHA_FIELDS: &[&str] = &[
+    "cf-turnstile-response",
+    "h-captcha-response",
+    "captcha_token",
+];
+
+#[derive(Deserialize)]
+struct SiteVerifyResponse {
+    success: bool,
+    #[serde(default, rename = "error-codes")]
+    error_codes: Vec<String>,
+}
+
+/// Why a CAPTCHA token was not accepted.
+pub(crate) enum CaptchaError {
+    /// Missing, expired or forged token.
+    Invalid(String),
+    /// The provider could not be asked.
+    Unavailable(String),
+}
+
+/// Asks the provider whether `token` is a solved challenge for our site key.
+pub(crate) async fn verify_captcha(
+    client: &reqwest::Client,
+    captcha: &Captcha,
+    token: &str,
+) -> Result<(), CaptchaError> {
+    let token = token.trim();
+    if token.is_empty() {
+        return Err(CaptchaError::Invalid("missing token".to_string()));
+    }
+    let mut params = vec![("secret", captcha.secret.as_str()), ("response", token)];
+    let url = match captcha.provider {
+        CaptchaProvider::Turnstile => TURNSTILE_VERIFY_URL,
+        CaptchaProvider::Hcaptcha => {
+            // Also rejects tokens solved for another site
+            params.push(("sitekey", captcha.site_key.as_str()));
+            HCAPTCHA_VERIFY_URL
+        }
+    };
+    let response = client
+        .post(url)
+        .form(&params)
+        .send()
+        .await
+        .map_err(|e| CaptchaError::Unavailable(e.to_string()))?;
+    if !response.status().is_success() {
+        return Err(CaptchaError::Unavailable(format!(
+            "verification returned {}",
+            response.status()
+        )));
+    }
+    let verdict: SiteVerifyResponse = response
+        .json()
+        .await
+        .map_err(|e| CaptchaError::Unavailable(e.to_string()))?;
+    if verdict.success {
+        Ok(())
+    } else {
+        Err(CaptchaError::Invalid(verdict.error_codes.join(", ")))
+    }
+}
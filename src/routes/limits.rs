@@ -0,0 +1,65 @@
+use axum::{
+    extract::{Request, State},
+    http::{header, StatusCode},
+    middleware::Next,
+    response::{Html, IntoResponse, Response},
+};
+use std::sync::Arc;
+
+use crate::state::AppState;
+
+/// Explanation sent when a request body exceeds `MAX_UPLOAD_BYTES`.
+pub(crate) fn upload_too_large_message(max_bytes: usize) -> String {
+    format!(
+        "The upload is too large. Papers can be at most {:.1} MB ({} bytes).",
+        max_bytes as f64 / (1024.0 * 1024.0),
+        max_bytes
+    )
+}
+
+/// 413 page for browser form posts.
+pub(crate) fn upload_too_large_page(max_bytes: usize) -> Response {
+    (
+        StatusCode::PAYLOAD_TOO_LARGE,
+        Html(format!(
+            "<h1>Upload too large</h1><p>{}</p><p><a href=\"/\">Back to the upload form</a></p>",
+            upload_too_large_message(max_bytes)
+        )),
+    )
+        .into_response()
+}
+
+/// Replaces the framework's bare plain-text 413 with a page for browser
+/// routes or JSON for `/api` routes. 413s the handlers build themselves
+/// already explain the problem and pass through unchanged.
+pub async fn friendly_payload_too_large(
+    State(state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let is_api = request.uri().path().starts_with("/api/");
+    let response = next.run(request).await;
+    let is_bare = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_none_or(|ct| ct.starts_with("text/plain"));
+    if response.status() != StatusCode::PAYLOAD_TOO_LARGE || !is_bare {
+        return response;
+    }
+
+    let max_bytes = state.config.max_upload_bytes;
+    if is_api {
+        (
+            StatusCode::PAYLOAD_TOO_LARGE,
+            axum::Json(serde_json::json!({
+                "status": "error",
+                "message": upload_too_large_message(max_bytes),
+                "max_bytes": max_bytes
+            })),
+        )
+            .into_response()
+    } else {
+        upload_too_large_page(max_bytes)
+    }
+}
@@ -1,5 +1,14 @@
+mod admin;
 mod api;
+mod captcha;
+mod limits;
 mod pages;
+mod tenant;
+mod ws;
 
+pub use admin::require_admin;
 pub use api::*;
+pub use limits::*;
 pub use pages::*;
+pub use tenant::require_tenant;
+pub use ws::*;
@@ -1,207 +1,1785 @@
 use axum::{
-    extract::{Path, State},
+    extract::{ConnectInfo, Path, Query, State},
+    http::{HeaderMap, StatusCode},
     response::{Html, IntoResponse, Redirect},
-    Form,
 };
-use serde::Deserialize;
+use std::collections::HashMap;
+use std::net::SocketAddr;
 use std::sync::Arc;
-use tera::{Context, Tera};
+use tera::Context;
 
-use crate::db::{create_submission, get_review_results, get_submission};
-use crate::state::AppState;
+use super::tenant::{in_tenant, request_tenant};
+use crate::audit;
+use crate::config::{
+    ActiveContentPolicy, AmbiguousDecisionPolicy, CertificateFormat, DisagreementPolicy,
+    DuplicateTitlePolicy, LanguagePolicy, ReviewFormat, TitleLengthPolicy,
+};
+use crate::db::{
+    create_submission, get_review_results, get_submission, NewSubmission, ReviewResult,
+    ReviewResultDisplay, Submission,
+};
+use crate::state::{AppState, InFlightUpload};
 use crate::storage::generate_submission_id;
 
-pub async fn index(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+pub async fn index(State(state): State<Arc<AppState>>, headers: HeaderMap) -> impl IntoResponse {
+    render_template(&state, "index.html", index_context(&state, &headers)).await
+}
+
+fn index_context(state: &AppState, headers: &HeaderMap) -> Context {
     let mut ctx = Context::new();
-    render_template(&state, "index.html", ctx).await
+    ctx.insert(
+        "branding",
+        state.config.branding(request_tenant(state, headers)),
+    );
+    ctx.insert("title_max_chars", &state.config.title_max_chars);
+    let window = &state.config.submission_window;
+    ctx.insert(
+        "submissions_closed",
+        &window.closed_message(chrono::Utc::now()),
+    );
+    ctx.insert(
+        "submissions_close_at",
+        &window.closes_at.map(crate::config::format_window_time),
+    );
+    let captcha = state.config.captcha.as_ref();
+    ctx.insert("captcha_provider", &captcha.map(|c| c.provider));
+    ctx.insert("captcha_site_key", &captcha.map(|c| &c.site_key));
+    ctx
 }
 
-#[derive(Deserialize)]
-pub struct UploadForm {
-    paper_title: Option<String>,
+/// The upload form again, with the reason the upload was refused.
+async fn upload_refused(
+    state: &AppState,
+    headers: &HeaderMap,
+    rejection: UploadRejection,
+) -> axum::response::Response {
+    let mut ctx = index_context(state, headers);
+    ctx.insert("upload_error", &rejection.message);
+    (
+        rejection.status,
+        render_template(state, "index.html", ctx).await,
+    )
+        .into_response()
 }
 
 pub async fn upload_handler(
     State(state): State<Arc<AppState>>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
     mut multipart: axum::extract::Multipart,
 ) -> impl IntoResponse {
-    let mut paper_title = String::new();
-    let mut paper_data: Option<Vec<u8>> = None;
-    let mut filename = String::new();
+    if state.maintenance.is_enabled() {
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Html(format!(
+                "<h1>Down for maintenance</h1><p>{}</p>",
+                MAINTENANCE_MESSAGE
+            )),
+        )
+            .into_response();
+    }
+    if let Some(message) = state
+        .config
+        .submission_window
+        .closed_message(chrono::Utc::now())
+    {
+        return (
+            StatusCode::FORBIDDEN,
+            Html(format!(
+                "<h1>Submissions closed</h1><p>{}</p><p>Existing results remain available.</p>",
+                message
+            )),
+        )
+            .into_response();
+    }
+    // A body that isn't a usable multipart form is a client bug; report it
+    // rather than redirecting as for other failures
+    let mut form = match read_upload_form(&mut multipart, state.config.max_upload_bytes).await {
+        Ok(form) => form,
+        Err(rejection) if rejection.status == StatusCode::PAYLOAD_TOO_LARGE => {
+            return super::limits::upload_too_large_page(state.config.max_upload_bytes)
+        }
+        Err(rejection) => return rejection.into_response(),
+    };
+    form.tenant = request_tenant(&state, &headers).map(str::to_string);
+    let actor = audit::actor(&state, &headers, addr);
+    if let Err(rejection) = check_captcha(&state, &form).await {
+        audit::record(
+            &state,
+            "upload",
+            None,
+            &actor,
+            audit::FAILURE,
+            Some(&rejection.message),
+        )
+        .await;
+        return upload_refused(&state, &headers, rejection).await;
+    }
+    match start_submission(&state, form, false).await {
+        Ok(created) => {
+            audit::record(
+                &state,
+                "upload",
+                Some(&created.submission_id),
+                &actor,
+                audit::SUCCESS,
+                None,
+            )
+            .await;
+            Redirect::to(&results_location(&created)).into_response()
+        }
+        Err(rejection) => {
+            audit::record(
+                &state,
+                "upload",
+                None,
+                &actor,
+                audit::FAILURE,
+                Some(&rejection.message),
+            )
+            .await;
+            upload_refused(&state, &headers, rejection).await
+        }
+    }
+}
+
+/// Where the form sends the author after an upload: their results page,
+/// with the token and any upload warnings as repeated `?warning=`.
+fn results_location(created: &CreatedSubmission) -> String {
+    let mut location = format!(
+        "/results/{}?token={}",
+        created.submission_id, created.author_token
+    );
+    for warning in &created.warnings {
+        location.push_str("&warning=");
+        location.extend(percent_encoding::utf8_percent_encode(
+            warning,
+            percent_encoding::NON_ALPHANUMERIC,
+        ));
+    }
+    location
+}
+
+pub(crate) const MAINTENANCE_MESSAGE: &str = "New submissions are paused for maintenance. \
+Existing results and downloads are still available; please try again later.";
+
+/// A newly recorded submission and the author token that controls it.
+pub(crate) struct CreatedSubmission {
+    pub submission_id: String,
+    pub author_token: String,
+    /// Non-blocking notices about the upload, such as a duplicate title.
+    pub warnings: Vec<String>,
+}
 
-    while let Ok(Some(field)) = multipart.next_field().await {
+#[derive(serde::Deserialize)]
+pub struct AuthorTokenQuery {
+    pub(crate) token: Option<String>,
+}
+
+/// The author token sent as `?token=` or in the `X-Author-Token` header.
+pub(crate) fn request_author_token<'a>(
+    query_token: Option<&'a str>,
+    headers: &'a HeaderMap,
+) -> Option<&'a str> {
+    query_token
+        .or_else(|| headers.get("X-Author-Token").and_then(|v| v.to_str().ok()))
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+}
+
+/// True when `token` belongs to the submission. Submissions created before
+/// author tokens existed have no owner and accept any request.
+pub(crate) fn author_token_matches(submission: &Submission, token: Option<&str>) -> bool {
+    match &submission.author_token_hash {
+        None => true,
+        Some(hash) => token.is_some_and(|t| crate::storage::hash_author_token(t) == *hash),
+    }
+}
+
+/// Read access: open unless `REQUIRE_AUTHOR_TOKEN_FOR_VIEW` is set.
+pub(crate) fn may_view(state: &AppState, submission: &Submission, token: Option<&str>) -> bool {
+    !state.config.require_author_token_for_view || author_token_matches(submission, token)
+}
+
+/// True once the submission's `expires_at` has passed.
+pub(crate) fn submission_expired(submission: &Submission) -> bool {
+    submission
+        .expires_at
+        .is_some_and(|at| at <= chrono::Utc::now())
+}
+
+/// 410 response for the results or files of an expired submission.
+pub(crate) fn expired_response(submission: &Submission) -> axum::response::Response {
+    let expired_on = submission
+        .expires_at
+        .map(|at| at.format("%Y-%m-%d %H:%M UTC").to_string())
+        .unwrap_or_default();
+    (
+        StatusCode::GONE,
+        Html(format!(
+            "<h1>Submission expired</h1><p>The results for submission {} expired on {} and are no longer available.</p>",
+            submission.submission_id, expired_on
+        )),
+    )
+        .into_response()
+}
+
+/// Fields collected from an upload multipart body.
+#[derive(Default)]
+pub(crate) struct UploadForm {
+    paper_title: String,
+    category: String,
+    filename: String,
+    content_type: Option<String>,
+    paper_data: Option<Vec<u8>>,
+    /// Submission this upload revises, if the author named one.
+    parent_submission_id: String,
+    captcha_token: String,
+    /// Set by the handler from the request, not read from the form.
+    pub(crate) tenant: Option<String>,
+}
+
+/// Why an upload was refused. The form route shows `message` above the upload
+/// form with `status`; the JSON API reports both to the client.
+pub(crate) struct UploadRejection {
+    pub status: StatusCode,
+    pub message: String,
+}
+
+impl UploadRejection {
+    fn new(status: StatusCode, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+        }
+    }
+}
+
+/// Extraction failures describe a problem with the uploaded file itself.
+impl From<crate::agents::AgentError> for UploadRejection {
+    fn from(e: crate::agents::AgentError) -> Self {
+        Self::new(StatusCode::UNPROCESSABLE_ENTITY, e.to_string())
+    }
+}
+
+impl IntoResponse for UploadRejection {
+    fn into_response(self) -> axum::response::Response {
+        (
+            self.status,
+            axum::Json(serde_json::json!({
+                "status": "error",
+                "message": self.message
+            })),
+        )
+            .into_response()
+    }
+}
+
+/// Reads the upload fields. A body over `max_bytes` is rejected with 413; one
+/// that fails to parse, or has no fields at all, with 400.
+pub(crate) async fn read_upload_form(
+    multipart: &mut axum::extract::Multipart,
+    max_bytes: usize,
+) -> Result<UploadForm, UploadRejection> {
+    let mut form = UploadForm::default();
+    let mut field_count = 0;
+
+    loop {
+        let field = match multipart.next_field().await {
+            Ok(Some(field)) => field,
+            Ok(None) => break,
+            Err(e) => return Err(multipart_rejection(e, max_bytes)),
+        };
+        field_count += 1;
         let name = field.name().unwrap_or("").to_string();
+        let malformed = |e| multipart_rejection(e, max_bytes);
         if name == "paper_title" {
-            if let Ok(text) = field.text().await {
-                paper_title = text;
-            }
+            form.paper_title = field.text().await.map_err(malformed)?;
+        } else if name == "category" {
+            form.category = field.text().await.map_err(malformed)?;
+        } else if name == "parent_submission_id" {
+            form.parent_submission_id = field.text().await.map_err(malformed)?;
+        } else if super::captcha::CAPTCHA_FIELDS.contains(&name.as_str()) {
+            form.captcha_token = field.text().await.map_err(malformed)?;
         } else if name == "paper" {
-            if let Ok(data) = field.bytes().await {
-                paper_data = Some(data.to_vec());
-            }
-            filename = field.file_name().unwrap_or("paper.pdf").to_string();
+            form.filename = field.file_name().unwrap_or("paper.pdf").to_string();
+            form.content_type = field.content_type().map(str::to_string);
+            form.paper_data = Some(field.bytes().await.map_err(malformed)?.to_vec());
         }
     }
 
+    if field_count == 0 {
+        return Err(UploadRejection::new(
+            StatusCode::BAD_REQUEST,
+            "empty request",
+        ));
+    }
+
+    Ok(form)
+}
+
+/// Refuses the upload with 400 unless it carries a valid CAPTCHA token, when
+/// `CAPTCHA_SECRET` is set.
+pub(crate) async fn check_captcha(
+    state: &AppState,
+    form: &UploadForm,
+) -> Result<(), UploadRejection> {
+    let Some(captcha) = &state.config.captcha else {
+        return Ok(());
+    };
+    match super::captcha::verify_captcha(&state.http, captcha, &form.captcha_token).await {
+        Ok(()) => Ok(()),
+        Err(super::captcha::CaptchaError::Invalid(reason)) => {
+            tracing::info!("Upload refused, CAPTCHA not passed: {}", reason);
+            Err(UploadRejection::new(
+                StatusCode::BAD_REQUEST,
+                "CAPTCHA verification failed; please complete the challenge and try again.",
+            ))
+        }
+        Err(super::captcha::CaptchaError::Unavailable(e)) => {
+            tracing::error!("CAPTCHA verification unavailable: {}", e);
+            Err(UploadRejection::new(
+                StatusCode::SERVICE_UNAVAILABLE,
+                "CAPTCHA verification is unavailable; please try again later.",
+            ))
+        }
+    }
+}
+
+fn multipart_rejection(
+    e: axum::extract::multipart::MultipartError,
+    max_bytes: usize,
+) -> UploadRejection {
+    if e.status() == StatusCode::PAYLOAD_TOO_LARGE {
+        UploadRejection::new(
+            StatusCode::PAYLOAD_TOO_LARGE,
+            super::limits::upload_too_large_message(max_bytes),
+        )
+    } else {
+        UploadRejection::new(
+            StatusCode::BAD_REQUEST,
+            format!("Malformed multipart body: {}", e),
+        )
+    }
+}
+
+/// Validates and stores an upload, records the submission and starts the
+/// reviews. With `inline` the reviews run before returning (only for papers
+/// up to `sync_review_max_bytes`); otherwise they run in a background task.
+/// Returns the new submission ID and its author token.
+pub(crate) async fn start_submission(
+    state: &AppState,
+    form: UploadForm,
+    inline: bool,
+) -> Result<CreatedSubmission, UploadRejection> {
+    let UploadForm {
+        paper_title,
+        category,
+        filename,
+        content_type,
+        paper_data,
+        parent_submission_id,
+        captcha_token: _,
+        tenant,
+    } = form;
+
     let paper_data = match paper_data {
         Some(d) if !d.is_empty() => d,
-        _ => return Redirect::to("/").into_response(),
+        _ => {
+            return Err(UploadRejection::new(
+                StatusCode::BAD_REQUEST,
+                "No paper uploaded.",
+            ))
+        }
     };
 
     if !filename.to_lowercase().ends_with(".pdf") {
-        return Redirect::to("/").into_response();
+        return Err(UploadRejection::new(
+            StatusCode::BAD_REQUEST,
+            "Only PDF files are accepted.",
+        ));
     }
 
-    let submission_id = generate_submission_id();
-    let upload_path = state
+    let title = resolve_title(&paper_title, &filename, state.config.require_title)?;
+
+    check_title_length(
+        &paper_title,
+        state.config.title_max_chars,
+        state.config.title_length_policy,
+    )?;
+
+    if inline && paper_data.len() > state.config.sync_review_max_bytes {
+        return Err(UploadRejection::new(
+            StatusCode::PAYLOAD_TOO_LARGE,
+            format!(
+                "Synchronous review is limited to papers up to {} bytes. Submit without sync=true and poll /api/status for the result.",
+                state.config.sync_review_max_bytes
+            ),
+        ));
+    }
+
+    let category = resolve_category(
+        state.config.allowed_categories(tenant.as_deref()),
+        &category,
+    )
+    .map_err(|()| UploadRejection::new(StatusCode::BAD_REQUEST, "Unknown category."))?;
+
+    let mut parent_submission_id = match parent_submission_id.trim() {
+        "" => None,
+        parent => match get_submission(state.pool.as_ref(), parent).await {
+            Ok(Some(s)) if s.tenant_id == tenant => Some(parent.to_string()),
+            Ok(_) => {
+                return Err(UploadRejection::new(
+                    StatusCode::BAD_REQUEST,
+                    format!("Parent submission '{}' not found.", parent),
+                ))
+            }
+            Err(e) => {
+                tracing::error!("Failed to look up parent submission {}: {}", parent, e);
+                return Err(UploadRejection::new(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "Failed to look up parent submission.",
+                ));
+            }
+        },
+    };
+
+    let max_pages = state.config.max_pdf_pages;
+    let reject_active = state.config.active_content_policy == ActiveContentPolicy::Reject;
+    let (paper_data, active_content, extracted) = state
+        .extraction_pool
+        .run(move || {
+            // Checked first so a rejected file never reaches the extractor
+            let active_content = crate::agents::detect_active_content(&paper_data);
+            let extracted = (!reject_active || active_content.is_empty())
+                .then(|| crate::agents::extract_text_from_pdf(&paper_data, max_pages));
+            (paper_data, active_content, extracted)
+        })
+        .await
+        .map_err(|_| {
+            UploadRejection::new(StatusCode::INTERNAL_SERVER_ERROR, "PDF extraction failed.")
+        })?;
+    let Some(extracted) = extracted else {
+        tracing::warn!(
+            "Rejected {}: active content ({})",
+            filename,
+            active_content.join(", ")
+        );
+        return Err(UploadRejection::new(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            format!(
+                "This PDF contains active content ({}), which is not accepted. Please export \
+                 or print it to a plain PDF and upload it again.",
+                active_content.join(", ")
+            ),
+        ));
+    };
+    let extracted = extracted?;
+    let page_count = extracted.page_count;
+    let too_short = state
+        .config
+        .min_page_count
+        .is_some_and(|min| page_count < min);
+    let too_long = state
         .config
-        .upload_folder
-        .join(format!("{}_{}", submission_id, filename));
+        .max_page_count
+        .is_some_and(|max| page_count > max);
+    if too_short || too_long {
+        let limits = match (state.config.min_page_count, state.config.max_page_count) {
+            (Some(min), Some(max)) => format!("between {} and {} pages", min, max),
+            (Some(min), None) => format!("at least {} pages", min),
+            (None, Some(max)) => format!("at most {} pages", max),
+            (None, None) => unreachable!(),
+        };
+        return Err(UploadRejection::new(
+            StatusCode::BAD_REQUEST,
+            format!("Papers must be {}; this one has {}.", limits, page_count),
+        ));
+    }
+    let extracted = reextract_if_garbled(state, extracted, &paper_data).await;
+    let extraction_quality = extracted.quality;
+    let paper_text = extracted.text;
 
-    if std::fs::write(&upload_path, &paper_data).is_err() {
-        return Redirect::to("/").into_response();
+    let estimated_tokens = crate::agents::estimate_review_tokens(&state.config, &paper_text)
+        * state.config.reviewer_count as u64;
+    tracing::info!(
+        "Paper {} estimated at {} tokens across {} reviewers",
+        filename,
+        estimated_tokens,
+        state.config.reviewer_count
+    );
+    if let Some(budget) = state.config.max_estimated_tokens {
+        if estimated_tokens > budget {
+            return Err(UploadRejection::new(
+                StatusCode::PAYLOAD_TOO_LARGE,
+                format!(
+                    "This paper is too long to review: it would use about {} tokens, above the {}-token limit per submission.",
+                    estimated_tokens, budget
+                ),
+            ));
+        }
     }
 
-    let title = if paper_title.trim().is_empty() {
-        filename.replace(".pdf", "")
-    } else {
-        paper_title.trim().to_string()
+    let mut flags = Vec::new();
+    if !active_content.is_empty() {
+        tracing::warn!(
+            "Paper {} contains active content ({})",
+            filename,
+            active_content.join(", ")
+        );
+        flags.push(format!("active_content:{}", active_content.join(",")));
+    }
+    if let (Some(total), Some(max_pages)) = (extracted.truncated_from, max_pages) {
+        tracing::info!(
+            "Paper {} truncated to {} of {} pages",
+            filename,
+            max_pages,
+            total
+        );
+        flags.push(format!("truncated_pages:{}/{}", max_pages, total));
+    }
+    if extraction_quality < state.config.extraction_quality_threshold {
+        tracing::warn!(
+            "Paper {} text looks garbled (quality {:.2})",
+            filename,
+            extraction_quality
+        );
+        flags.push(format!("low_extraction_quality:{:.2}", extraction_quality));
+    }
+    let detected_language = crate::agents::detect_language(&paper_text);
+    let allowed_languages = &state.config.allowed_languages;
+    if !allowed_languages.is_empty()
+        && !detected_language.is_some_and(|l| allowed_languages.iter().any(|a| a == l))
+    {
+        let detected = detected_language.unwrap_or("unknown");
+        if state.config.language_policy == LanguagePolicy::Reject {
+            return Err(UploadRejection::new(
+                StatusCode::UNPROCESSABLE_ENTITY,
+                format!(
+                    "Papers must be written in one of: {}. The uploaded paper appears to be in '{}'.",
+                    allowed_languages.join(", "),
+                    detected
+                ),
+            ));
+        }
+        flags.push(format!("unsupported_language:{}", detected));
+    }
+
+    if crate::agents::paper_contains_injection(&paper_text) {
+        tracing::warn!("Paper {} contains reviewer-directed instructions", filename);
+        flags.push("possible_prompt_injection".to_string());
+    }
+
+    let title = truncate_title(&title, state.config.title_max_chars);
+
+    let submission_id = generate_submission_id();
+    let author_token = crate::storage::generate_author_token();
+
+    // A re-upload of a paper that is still being reviewed gets the running
+    // submission back rather than a second, concurrent review set
+    let mut content_hash = crate::storage::content_hash(&paper_data);
+    if let Some(tenant) = &tenant {
+        content_hash = format!("{}:{}", tenant, content_hash);
+    }
+    let in_flight = match state.in_flight_uploads.claim(
+        &content_hash,
+        InFlightUpload {
+            submission_id: submission_id.clone(),
+            author_token: author_token.clone(),
+        },
+    ) {
+        Ok(guard) => guard,
+        Err(existing) => {
+            tracing::info!(
+                "Upload {} matches in-flight submission {}; not starting new reviews",
+                filename,
+                existing.submission_id
+            );
+            return Ok(CreatedSubmission {
+                submission_id: existing.submission_id,
+                author_token: existing.author_token,
+                warnings: vec![
+                    "This paper is already being reviewed; returning the submission in progress."
+                        .to_string(),
+                ],
+            });
+        }
     };
 
-    if let Err(e) = create_submission(
-        state.pool.as_ref(),
+    let mut warnings = Vec::new();
+    if state.config.duplicate_title_policy != DuplicateTitlePolicy::Off {
+        let normalized = normalize_title(&title);
+        match crate::db::find_submission_by_normalized_title(
+            state.pool.as_ref(),
+            &normalized,
+            tenant.as_deref(),
+        )
+        .await
+        {
+            Ok(Some(existing)) => {
+                if state.config.duplicate_title_policy == DuplicateTitlePolicy::Block {
+                    return Err(UploadRejection::new(
+                        StatusCode::CONFLICT,
+                        format!(
+                            "A paper with this title was already submitted ({}).",
+                            existing
+                        ),
+                    ));
+                }
+                warnings.push(format!(
+                    "A paper with the same title was already submitted ({}); this looks like a resubmission.",
+                    existing
+                ));
+                flags.push(format!("duplicate_title:{}", existing));
+                // An accepted resubmission of the same title revises the
+                // earlier one unless the author linked it explicitly
+                parent_submission_id.get_or_insert(existing);
+            }
+            Ok(None) => {}
+            Err(e) => tracing::warn!("Duplicate title check failed: {}", e),
+        }
+    }
+
+    // Each tenant's uploads are kept in their own folder
+    let upload_folder = match &tenant {
+        Some(tenant) => {
+            let folder = state.config.upload_folder.join(tenant);
+            if let Err(e) = crate::storage::create_dir(&folder, state.config.dir_mode) {
+                tracing::error!("Failed to create upload folder {}: {}", folder.display(), e);
+                return Err(UploadRejection::new(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "Failed to store upload.",
+                ));
+            }
+            folder
+        }
+        None => state.config.upload_folder.clone(),
+    };
+    let upload_path = upload_folder.join(crate::storage::upload_file_name(
+        state.config.upload_naming,
         &submission_id,
-        &title,
         &filename,
-        upload_path.to_str().unwrap_or(""),
+    ));
+
+    let upload_len = paper_data.len() as u64;
+    if let Some(quota) = state.config.storage_quota_bytes {
+        if !state.storage_usage.try_reserve(upload_len, quota) {
+            tracing::warn!(
+                "Storage quota reached ({} of {} bytes used); refusing {}",
+                state.storage_usage.current(),
+                quota,
+                filename
+            );
+            return Err(UploadRejection::new(
+                StatusCode::INSUFFICIENT_STORAGE,
+                "The server is out of storage for new submissions. Please try again later.",
+            ));
+        }
+    } else {
+        state.storage_usage.add(upload_len);
+    }
+    // Every early return from here on gives the space back
+    let mut pending_upload = crate::storage::PendingUpload::new(&state.storage_usage, upload_len);
+
+    if crate::storage::write_atomic(
+        &state.config.temp_folder,
+        &upload_path,
+        &paper_data,
+        state.config.file_mode,
     )
-    .await
+    .is_err()
     {
+        return Err(UploadRejection::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Failed to store upload.",
+        ));
+    }
+    pending_upload.stored_at(&upload_path);
+
+    let author_token_hash = crate::storage::hash_author_token(&author_token);
+
+    let new_submission = NewSubmission {
+        submission_id: &submission_id,
+        paper_title: &title,
+        filename: &filename,
+        file_path: upload_path.to_str().unwrap_or(""),
+        category: category.as_deref(),
+        detected_language,
+        flags: &flags,
+        content_type: content_type.as_deref(),
+        author_token_hash: &author_token_hash,
+        parent_submission_id: parent_submission_id.as_deref(),
+        expires_at: state
+            .config
+            .submission_ttl_days
+            .map(|days| chrono::Utc::now() + chrono::Duration::days(days)),
+        extraction_quality,
+        page_count: page_count as i32,
+        tenant_id: tenant.as_deref(),
+        active_content: &active_content,
+    };
+    if let Err(e) = create_submission(state.pool.as_ref(), &new_submission).await {
         tracing::error!("Failed to create submission: {}", e);
-        return Redirect::to("/").into_response();
+        return Err(UploadRejection::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Failed to create submission.",
+        ));
     }
 
-    // Spawn background review processing
-    let pool = state.pool.clone();
-    let config = state.config.clone();
-    let sub_id = submission_id.clone();
-    let path = upload_path.clone();
-    tokio::spawn(async move {
-        if let Err(e) = process_reviews_background(pool, config, sub_id, path, title, filename).await
-        {
-            tracing::error!("Background review failed: {}", e);
+    crate::events::publish(
+        &state.events,
+        crate::events::SubmissionEvent::Created {
+            submission_id: submission_id.clone(),
+            paper_title: title.clone(),
+        },
+    );
+
+    if inline {
+        // A failed inline review is recorded on the submission, which keeps its file
+        pending_upload.keep();
+        let _in_flight = in_flight;
+        process_reviews_background(
+            state.clone(),
+            submission_id.clone(),
+            paper_text,
+            title,
+            tenant,
+        )
+        .await
+        .map_err(|e| {
+            tracing::error!("Synchronous review failed: {}", e);
+            UploadRejection::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Review processing failed.",
+            )
+        })?;
+        return Ok(CreatedSubmission {
+            submission_id,
+            author_token,
+            warnings,
+        });
+    }
+
+    // Reviews run on the queue workers; the paper is re-read from disk there
+    if let Err(e) = state
+        .review_queue
+        .enqueue(state.pool.as_ref(), &submission_id, in_flight)
+        .await
+    {
+        tracing::error!("Failed to queue reviews for {}: {}", submission_id, e);
+        if let Err(e) = crate::db::delete_submission(state.pool.as_ref(), &submission_id).await {
+            tracing::error!(
+                "Failed to remove unqueued submission {}: {}",
+                submission_id,
+                e
+            );
         }
-    });
+        return Err(UploadRejection::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Failed to queue the submission for review.",
+        ));
+    }
+    pending_upload.keep();
+
+    Ok(CreatedSubmission {
+        submission_id,
+        author_token,
+        warnings,
+    })
+}
+
+/// Lowercased title with punctuation and whitespace runs collapsed to single
+/// spaces; must match the normalization in `find_submission_by_normalized_title`.
+fn normalize_title(title: &str) -> String {
+    title
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
 
-    Redirect::to(&format!("/results/{}", submission_id)).into_response()
+/// Normalizes the submitted category. An empty value means "no category";
+/// when an allow-list is configured the value must match one of its entries
+/// (case-insensitively) and is stored using the configured spelling.
+fn resolve_category(allowed: &[String], raw: &str) -> Result<Option<String>, ()> {
+    let raw = raw.trim();
+    if raw.is_empty() {
+        return Ok(None);
+    }
+    if allowed.is_empty() {
+        return Ok(Some(raw.to_string()));
+    }
+    allowed
+        .iter()
+        .find(|c| c.eq_ignore_ascii_case(raw))
+        .map(|c| Some(c.clone()))
+        .ok_or(())
+}
+
+/// Runs the reviews of a queued submission, re-extracting its text from the
+/// stored upload. Already completed submissions are left alone.
+pub(crate) async fn run_review_job(state: &AppState, submission_id: &str) -> Result<(), String> {
+    let submission = get_submission(state.pool.as_ref(), submission_id)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Submission {} not found", submission_id))?;
+    if submission.processing_complete {
+        return Ok(());
+    }
+
+    let paper_data = tokio::fs::read(&submission.file_path)
+        .await
+        .map_err(|e| format!("Failed to read upload: {}", e))?;
+    let max_pages = state.config.max_pdf_pages;
+    let (paper_data, extracted) = state
+        .extraction_pool
+        .run(move || {
+            let extracted = crate::agents::extract_text_from_pdf(&paper_data, max_pages);
+            (paper_data, extracted)
+        })
+        .await
+        .map_err(|_| "PDF extraction failed".to_string())?;
+    let extracted = extracted.map_err(|e| e.to_string())?;
+    let extracted = reextract_if_garbled(state, extracted, &paper_data).await;
+
+    let filename = submission.filename.unwrap_or_default();
+    let title = submission.paper_title.unwrap_or(filename);
+    process_reviews_background(
+        state.clone(),
+        submission_id.to_string(),
+        extracted.text,
+        title,
+        submission.tenant_id,
+    )
+    .await
+}
+
+/// Retries extraction with `PDFTOTEXT_PATH` when the text scores below
+/// `EXTRACTION_QUALITY_THRESHOLD`.
+async fn reextract_if_garbled(
+    state: &AppState,
+    extracted: crate::agents::ExtractedText,
+    paper_data: &[u8],
+) -> crate::agents::ExtractedText {
+    crate::agents::reextract_if_garbled(
+        extracted,
+        paper_data,
+        &state.config.pdftotext_path,
+        &state.config.temp_folder,
+        state.config.max_pdf_pages,
+        state.config.extraction_quality_threshold,
+    )
+    .await
+}
+
+/// The submitted title, trimmed. A blank one is refused with 400 when
+/// `REQUIRE_TITLE` is set and otherwise falls back to the filename.
+fn resolve_title(
+    paper_title: &str,
+    filename: &str,
+    require_title: bool,
+) -> Result<String, UploadRejection> {
+    let paper_title = paper_title.trim();
+    if !paper_title.is_empty() {
+        return Ok(paper_title.to_string());
+    }
+    if require_title {
+        return Err(UploadRejection::new(
+            StatusCode::BAD_REQUEST,
+            "A paper title is required.",
+        ));
+    }
+    Ok(filename.replace(".pdf", ""))
+}
+
+/// Refuses a submitted title over `max_chars` characters with 400 under
+/// `TITLE_LENGTH_POLICY=reject`; otherwise it is truncated later.
+fn check_title_length(
+    paper_title: &str,
+    max_chars: usize,
+    policy: TitleLengthPolicy,
+) -> Result<(), UploadRejection> {
+    if policy == TitleLengthPolicy::Reject && paper_title.trim().chars().count() > max_chars {
+        return Err(UploadRejection::new(
+            StatusCode::BAD_REQUEST,
+            format!("The paper title must be at most {} characters.", max_chars),
+        ));
+    }
+    Ok(())
+}
+
+/// Cuts `title` to at most `max_chars` characters, ending in "..." when
+/// anything was removed.
+fn truncate_title(title: &str, max_chars: usize) -> String {
+    if title.chars().count() <= max_chars {
+        return title.to_string();
+    }
+    let kept: String = title.chars().take(max_chars.saturating_sub(3)).collect();
+    format!("{}...", kept.trim_end())
 }
 
 async fn process_reviews_background(
-    pool: crate::db::DbPool,
-    config: Arc<crate::config::Config>,
+    state: AppState,
     submission_id: String,
-    upload_path: std::path::PathBuf,
+    paper_text: String,
     paper_title: String,
-    filename: String,
+    tenant: Option<String>,
 ) -> Result<(), String> {
-    let reviewers = ["Reviewer 1", "Reviewer 2", "Reviewer 3"];
-    let path_str = upload_path.to_str().ok_or("Invalid path")?;
+    let _lock = state
+        .submission_locks
+        .try_lock(&submission_id)
+        .ok_or_else(|| format!("Submission {} is already being updated", submission_id))?;
 
-    let mut all_accepted = true;
+    let reviewers = state.config.reviewer_labels(tenant.as_deref());
 
-    for reviewer_name in reviewers {
-        let mut agent = crate::agents::ClaudeAgent::new(config.claude_api_key.clone());
+    let paper_text: Arc<str> = paper_text.into();
+    let mut outcomes = Vec::new();
 
-        match agent.analyze_paper(path_str).await {
-            Ok(review_text) => {
-                let decision = crate::agents::determine_decision(&review_text);
-                let decision_str = &decision.decision;
-                if decision_str != "ACCEPTED" {
-                    all_accepted = false;
+    if state.batches.is_some() {
+        // Queued together so every reviewer lands in the same batch
+        let mut runs = tokio::task::JoinSet::new();
+        for reviewer_name in reviewers.clone() {
+            let state = state.clone();
+            let submission_id = submission_id.clone();
+            let paper_text = paper_text.clone();
+            let tenant = tenant.clone();
+            runs.spawn(async move {
+                let batches = state.batches.clone();
+                run_reviewer(
+                    &state,
+                    &submission_id,
+                    &reviewer_name,
+                    &paper_text,
+                    state
+                        .config
+                        .reviewer_model(tenant.as_deref(), &reviewer_name),
+                    batches.as_ref(),
+                    true,
+                )
+                .await
+            });
+        }
+        while let Some(run) = runs.join_next().await {
+            outcomes.push(run.map_err(|e| format!("Reviewer task failed: {}", e))?);
+        }
+    } else {
+        for reviewer_name in &reviewers {
+            let model = state
+                .config
+                .reviewer_model(tenant.as_deref(), reviewer_name);
+            outcomes.push(
+                run_reviewer(
+                    &state,
+                    &submission_id,
+                    reviewer_name,
+                    &paper_text,
+                    model,
+                    None,
+                    true,
+                )
+                .await,
+            );
+        }
+    }
+
+    let mut all_accepted = outcomes.iter().all(|o| o.accepted);
+    // Every review is ACCEPTED or ACCEPTED WITH MINOR REVISION
+    let mut all_minor_or_better = outcomes.iter().all(|o| o.minor_or_better);
+
+    if state.config.disagreement_policy == DisagreementPolicy::Tiebreak && split_decision(&outcomes)
+    {
+        tracing::info!(
+            "Reviewers of {} disagree; running a tie-breaker",
+            submission_id
+        );
+        outcomes.push(
+            run_reviewer(
+                &state,
+                &submission_id,
+                TIEBREAK_REVIEWER,
+                &paper_text,
+                None,
+                None,
+                true,
+            )
+            .await,
+        );
+        let majority = |count: usize| count * 2 > outcomes.len();
+        all_accepted = majority(outcomes.iter().filter(|o| o.accepted).count());
+        all_minor_or_better = majority(outcomes.iter().filter(|o| o.minor_or_better).count());
+    }
+
+    // A queued job run again after an interruption may have stored reviews
+    for outcome in &outcomes {
+        let Some(review_id) = outcome.review_id else {
+            continue;
+        };
+        if let Err(e) = crate::db::supersede_review_results(
+            state.pool.as_ref(),
+            &submission_id,
+            &outcome.reviewer_name,
+            review_id,
+        )
+        .await
+        {
+            tracing::error!(
+                "Failed to supersede earlier reviews for {}: {}",
+                submission_id,
+                e
+            );
+        }
+    }
+
+    finalize_submission(
+        &state,
+        &submission_id,
+        &paper_title,
+        tenant.as_deref(),
+        all_accepted,
+        all_minor_or_better,
+    )
+    .await
+}
+
+/// Reviewer added under `DISAGREEMENT_POLICY=tiebreak` to settle a split.
+const TIEBREAK_REVIEWER: &str = "Tie-breaking Reviewer";
+
+/// True when one reviewer accepted and another rejected.
+fn split_decision(outcomes: &[ReviewerOutcome]) -> bool {
+    outcomes.iter().any(|o| o.accepted) && outcomes.iter().any(|o| o.rejected)
+}
+
+/// How one reviewer's run bears on the submission-level decision.
+struct ReviewerOutcome {
+    reviewer_name: String,
+    /// Row stored for this run; `None` when the insert failed.
+    review_id: Option<i32>,
+    /// Model that produced the review, if one did.
+    model_used: Option<String>,
+    accepted: bool,
+    rejected: bool,
+    /// ACCEPTED or ACCEPTED WITH MINOR REVISION.
+    minor_or_better: bool,
+}
+
+/// Runs one reviewer, optionally starting from `model` or through
+/// `batches`, and records the review (or the error) in `review_results`.
+/// With `REVIEW_CACHE` on, a review cached for the same paper, prompt, model
+/// and reviewer is reused unless `use_cache` is false (a forced refresh).
+async fn run_reviewer(
+    state: &AppState,
+    submission_id: &str,
+    reviewer_name: &str,
+    paper_text: &str,
+    model: Option<&str>,
+    batches: Option<&crate::agents::BatchCoordinator>,
+    use_cache: bool,
+) -> ReviewerOutcome {
+    let mut agent = crate::agents::ClaudeAgent::new(&state.config, state.http.clone());
+    if let Some(model) = model {
+        agent.start_with_model(model);
+    }
+
+    let start_model = agent.current_model().to_string();
+    let content_hash = crate::storage::content_hash(paper_text.as_bytes());
+    let prompt_hash = agent.prompt_hash();
+    let cache_key = state
+        .config
+        .review_cache
+        .then_some(crate::db::ReviewCacheKey {
+            content_hash: &content_hash,
+            prompt_hash: &prompt_hash,
+            model: &start_model,
+            reviewer_name,
+        });
+    let cached = match &cache_key {
+        Some(key) if use_cache => match crate::db::cached_review(state.pool.as_ref(), key).await {
+            Ok(cached) => cached.map(|(text, structured)| crate::agents::Review {
+                text,
+                structured: structured.and_then(|s| serde_json::from_value(s).ok()),
+            }),
+            Err(e) => {
+                tracing::warn!("Review cache lookup failed for {}: {}", submission_id, e);
+                None
+            }
+        },
+        _ => None,
+    };
+    let mut from_cache = cached.is_some();
+    if from_cache {
+        tracing::info!(
+            "{} for {} reused a cached review from {}",
+            reviewer_name,
+            submission_id,
+            start_model
+        );
+    }
+
+    let mut review = match (cached, batches) {
+        (Some(cached), _) => Ok(cached),
+        (None, Some(batches)) => {
+            agent
+                .generate_review_batched(batches, submission_id, paper_text)
+                .await
+        }
+        (None, None) => agent.generate_review(paper_text).await,
+    };
+    let mut attempt = 0;
+    while let Err(ref e) = review {
+        if attempt >= state.config.reviewer_retries || !e.is_retryable() {
+            break;
+        }
+        attempt += 1;
+        tracing::warn!(
+            "{} failed for {} ({}); retry {}/{}",
+            reviewer_name,
+            submission_id,
+            e,
+            attempt,
+            state.config.reviewer_retries
+        );
+        agent.prepare_retry();
+        review = agent.generate_review(paper_text).await;
+    }
+    if state.config.ambiguous_decision_policy == AmbiguousDecisionPolicy::Retry
+        && review
+            .as_ref()
+            .is_ok_and(|review| review.decision(state.config.summary_strategy).ambiguous)
+    {
+        tracing::info!(
+            "{} gave no FINAL DECISION for {}; retrying",
+            reviewer_name,
+            submission_id
+        );
+        agent.prepare_retry();
+        review = agent.generate_review(paper_text).await;
+        from_cache = false;
+    }
+
+    let mut model_sequence = agent.model_sequence();
+    if model_sequence.is_empty() && from_cache {
+        model_sequence.push(start_model.clone());
+    }
+    tracing::info!(
+        "{} for {} ran on: {}",
+        reviewer_name,
+        submission_id,
+        model_sequence.join(" -> ")
+    );
+    if state.config.store_token_usage {
+        let usage = agent.token_usage();
+        if let Err(e) = crate::db::add_submission_token_usage(
+            state.pool.as_ref(),
+            submission_id,
+            usage.input_tokens,
+            usage.output_tokens,
+        )
+        .await
+        {
+            tracing::warn!("Failed to record token usage for {}: {}", submission_id, e);
+        }
+    }
+    // Full per-request log only when opted in
+    let attempt_log = state
+        .config
+        .store_model_attempts
+        .then(|| serde_json::to_value(agent.attempts()).ok())
+        .flatten();
+
+    match review {
+        Ok(review) => {
+            // Only reviews from the requested model are cached, so a fallback
+            // answer is never served as that model's review
+            let model_used = agent.current_model();
+            let cacheable = !from_cache && model_used == start_model;
+            if let Some(key) = cache_key.as_ref().filter(|_| cacheable) {
+                let structured = review
+                    .structured
+                    .as_ref()
+                    .and_then(|s| serde_json::to_value(s).ok());
+                if let Err(e) = crate::db::store_cached_review(
+                    state.pool.as_ref(),
+                    key,
+                    &review.text,
+                    structured.as_ref(),
+                )
+                .await
+                {
+                    tracing::warn!("Failed to cache review for {}: {}", submission_id, e);
+                }
+            }
+            let mut decision = review.decision(state.config.summary_strategy);
+            if crate::agents::decision_marker_suspicious(&review.text) {
+                tracing::warn!(
+                    "{} for {} states its decision suspiciously early",
+                    reviewer_name,
+                    submission_id
+                );
+                decision.decision = "NEEDS_HUMAN".to_string();
+                decision.accepted = false;
+                decision.minor_revision = false;
+                if let Err(e) = crate::db::add_submission_flag(
+                    state.pool.as_ref(),
+                    submission_id,
+                    "suspicious_decision_marker",
+                )
+                .await
+                {
+                    tracing::error!("Failed to flag submission {}: {}", submission_id, e);
+                }
+            }
+            if decision.ambiguous {
+                if state.config.ambiguous_decision_policy != AmbiguousDecisionPolicy::Revision {
+                    decision.decision = "NEEDS_HUMAN".to_string();
+                    decision.accepted = false;
                 }
+                decision.minor_revision = false;
+                if let Err(e) = crate::db::add_submission_flag(
+                    state.pool.as_ref(),
+                    submission_id,
+                    "ambiguous_decision",
+                )
+                .await
+                {
+                    tracing::error!("Failed to flag submission {}: {}", submission_id, e);
+                }
+            }
+            let decision_str = &decision.decision;
+            let summary = decision.summary.clone();
+            let full_review = decision.full_review.clone();
+            let structured_review = review
+                .structured
+                .as_ref()
+                .and_then(|s| serde_json::to_value(s).ok());
+            let reasons = if decision.accepted {
+                Vec::new()
+            } else {
+                crate::agents::decision_reasons(
+                    &review.text,
+                    &summary,
+                    state.config.max_decision_reasons,
+                )
+            };
 
-                let summary = decision.summary.clone();
-                let full_review = decision.full_review.clone();
+            let confidence = review.structured.as_ref().and_then(|s| {
+                crate::agents::decision_confidence(
+                    &s.scores,
+                    &state.config.review_criteria,
+                    &state.config.decision_thresholds,
+                    state.config.decision_confidence_margin,
+                )
+            });
 
-                let _ = sqlx::query(
+            let inserted = crate::db::with_retry("Review insert", || {
+                sqlx::query_scalar::<_, i32>(
                     r#"
-                    INSERT INTO review_results (submission_id, reviewer_name, decision, summary, full_review, model_used)
-                    VALUES ($1, $2, $3, $4, $5, $6)
+                    INSERT INTO review_results (submission_id, reviewer_name, decision, summary, full_review, model_used, ambiguous,
+                                                structured_review, model_sequence, attempt_log, reasons, seed, cached,
+                                                weighted_score, confidence)
+                    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)
+                    RETURNING id
                     "#,
                 )
-                .bind(&submission_id)
+                .bind(submission_id)
                 .bind(reviewer_name)
                 .bind(decision_str)
                 .bind(&summary)
                 .bind(&full_review)
-                .bind(agent.current_model())
-                .execute(pool.as_ref())
-                .await;
+                .bind(model_used)
+                .bind(decision.ambiguous)
+                .bind(&structured_review)
+                .bind(&model_sequence)
+                .bind(&attempt_log)
+                .bind(&reasons)
+                .bind(state.config.review_seed)
+                .bind(from_cache)
+                .bind(confidence.map(|c| c.weighted_score))
+                .bind(confidence.map(|c| c.confidence))
+                .fetch_one(state.pool.as_ref())
+            })
+            .await;
+            let review_id = match inserted {
+                Ok(id) => Some(id),
+                Err(e) => {
+                    tracing::error!(
+                        "Failed to store {}'s review for {}: {}",
+                        reviewer_name,
+                        submission_id,
+                        e
+                    );
+                    None
+                }
+            };
+            state.status_cache.invalidate(submission_id);
+            crate::events::publish(
+                &state.events,
+                crate::events::SubmissionEvent::ReviewCompleted {
+                    submission_id: submission_id.to_string(),
+                    reviewer_name: reviewer_name.to_string(),
+                    decision: decision.decision.clone(),
+                },
+            );
+            ReviewerOutcome {
+                reviewer_name: reviewer_name.to_string(),
+                review_id,
+                model_used: Some(model_used.to_string()),
+                accepted: decision.accepted,
+                rejected: decision.decision == "REJECTED",
+                minor_or_better: decision.accepted || decision.minor_revision,
             }
-            Err(e) => {
-                all_accepted = false;
-                let _ = sqlx::query(
+        }
+        Err(e) => {
+            if let crate::agents::AgentError::Auth(_) = e {
+                tracing::error!("Claude API rejected the configured CLAUDE_API_KEY");
+            }
+            let e = e.to_string();
+            let inserted = crate::db::with_retry("Review error insert", || {
+                sqlx::query_scalar::<_, i32>(
                     r#"
-                    INSERT INTO review_results (submission_id, reviewer_name, decision, summary, full_review)
-                    VALUES ($1, $2, 'ERROR', $3, $4)
+                    INSERT INTO review_results (submission_id, reviewer_name, decision, summary, full_review,
+                                                model_sequence, attempt_log)
+                    VALUES ($1, $2, 'ERROR', $3, $4, $5, $6)
+                    RETURNING id
                     "#,
                 )
-                .bind(&submission_id)
+                .bind(submission_id)
                 .bind(reviewer_name)
                 .bind(&e)
                 .bind(&e)
-                .execute(pool.as_ref())
-                .await;
+                .bind(&model_sequence)
+                .bind(&attempt_log)
+                .fetch_one(state.pool.as_ref())
+            })
+            .await;
+            let review_id = match inserted {
+                Ok(id) => Some(id),
+                Err(db_error) => {
+                    tracing::error!(
+                        "Failed to store {}'s error for {}: {}",
+                        reviewer_name,
+                        submission_id,
+                        db_error
+                    );
+                    None
+                }
+            };
+            state.status_cache.invalidate(submission_id);
+            crate::events::publish(
+                &state.events,
+                crate::events::SubmissionEvent::ReviewCompleted {
+                    submission_id: submission_id.to_string(),
+                    reviewer_name: reviewer_name.to_string(),
+                    decision: "ERROR".to_string(),
+                },
+            );
+            ReviewerOutcome {
+                reviewer_name: reviewer_name.to_string(),
+                review_id,
+                model_used: None,
+                accepted: false,
+                rejected: false,
+                minor_or_better: false,
             }
         }
     }
+}
 
-    let certificate_filename = if all_accepted {
-        let cert_name = format!("{}_certificate.pdf", submission_id);
-        let cert_path = config.results_folder.join(&cert_name);
-        if crate::pdf::generate_certificate(&paper_title, &cert_path).is_ok() {
-            Some(cert_name)
-        } else {
-            None
+/// Issues the certificate the reviews earn, if any, marks the submission
+/// complete and announces it.
+async fn finalize_submission(
+    state: &AppState,
+    submission_id: &str,
+    paper_title: &str,
+    tenant: Option<&str>,
+    all_accepted: bool,
+    all_minor_or_better: bool,
+) -> Result<(), String> {
+    let latest = latest_reviews(state, submission_id).await;
+    let policy = state.config.disagreement_policy;
+    let reviewer_disagreement = policy != DisagreementPolicy::Off
+        && latest.iter().any(|r| r.decision == "ACCEPTED")
+        && latest.iter().any(|r| r.decision == "REJECTED");
+    let needs_human_review = reviewer_disagreement && policy == DisagreementPolicy::NeedsHuman;
+
+    // Reviewers whose current review is an ERROR
+    let reviewers = state.config.reviewer_labels(tenant);
+    let review_errors = reviewers
+        .iter()
+        .filter(|name| !latest.iter().any(|r| r.reviewer_name == **name))
+        .count();
+    let error = state
+        .config
+        .max_reviewer_errors
+        .filter(|max| review_errors > *max)
+        .map(|_| format!("{} of {} reviewers failed", review_errors, reviewers.len()));
+
+    // Escalated or failed: no outcome, and so no certificate
+    let (all_accepted, all_minor_or_better) = if needs_human_review || error.is_some() {
+        (false, false)
+    } else {
+        (all_accepted, all_minor_or_better)
+    };
+
+    let certificate_kind = if all_accepted {
+        Some(crate::pdf::CertificateKind::Accepted)
+    } else if all_minor_or_better && state.config.minor_revision_certificates {
+        Some(crate::pdf::CertificateKind::MinorRevision)
+    } else {
+        None
+    };
+
+    let issued_at = chrono::Utc::now();
+    let mut certificate_error = None;
+    let certificate_filename = if let Some(kind) = certificate_kind {
+        match write_certificate(state, submission_id, paper_title, kind, issued_at).await {
+            Ok(cert_name) => Some(cert_name),
+            Err(e) => {
+                tracing::error!("Certificate generation failed for {}: {}", submission_id, e);
+                certificate_error = Some(e);
+                None
+            }
         }
     } else {
         None
     };
+    let certificate_issued_at = certificate_filename.as_ref().map(|_| issued_at);
+    let certificate_kind = certificate_filename
+        .as_ref()
+        .and(certificate_kind)
+        .map(|kind| kind.as_str());
 
-    sqlx::query(
-        r#"
-        UPDATE submissions 
-        SET processing_complete = true, all_accepted = $2, certificate_filename = $3
-        WHERE submission_id = $1
-        "#,
-    )
-    .bind(&submission_id)
-    .bind(all_accepted)
-    .bind(&certificate_filename)
-    .execute(pool.as_ref())
+    let review_similarity = crate::agents::review_similarity(
+        &latest
+            .iter()
+            .filter_map(|r| r.full_review.as_deref())
+            .collect::<Vec<_>>(),
+    );
+    let outcome = if needs_human_review {
+        "Undecided; the reviewers disagreed, so an editor will make the final decision"
+    } else if all_accepted {
+        "Accepted"
+    } else if all_minor_or_better {
+        "Accepted with minor revisions"
+    } else {
+        "Not accepted; the reviewers asked for major revisions or rejected the paper"
+    };
+    let author_summary = if state.config.author_summary && error.is_none() {
+        generate_author_summary(state, submission_id, outcome, &latest).await
+    } else {
+        None
+    };
+
+    // The weakest review decides how settled the outcome is
+    let decision_confidence = latest.iter().filter_map(|r| r.confidence).reduce(f64::min);
+
+    crate::db::with_retry("Submission completion update", || {
+        sqlx::query(
+            r#"
+            UPDATE submissions 
+            SET processing_complete = true, all_accepted = $2, certificate_filename = $3,
+                certificate_issued_at = $4, certificate_kind = $5, review_similarity = $6,
+                author_summary = $7, certificate_error = $8, reviewer_disagreement = $9,
+                needs_human_review = $10, error = $11, decision_confidence = $12
+            WHERE submission_id = $1
+            "#,
+        )
+        .bind(submission_id)
+        .bind(all_accepted)
+        .bind(&certificate_filename)
+        .bind(certificate_issued_at)
+        .bind(certificate_kind)
+        .bind(review_similarity)
+        .bind(&author_summary)
+        .bind(&certificate_error)
+        .bind(reviewer_disagreement)
+        .bind(needs_human_review)
+        .bind(&error)
+        .bind(decision_confidence)
+        .execute(state.pool.as_ref())
+    })
     .await
     .map_err(|e| e.to_string())?;
 
+    if let Some(similarity) = review_similarity {
+        if similarity >= state.config.review_similarity_threshold {
+            tracing::warn!(
+                "Reviews for {} are suspiciously similar ({:.2})",
+                submission_id,
+                similarity
+            );
+            if let Err(e) = crate::db::add_submission_flag(
+                state.pool.as_ref(),
+                submission_id,
+                "similar_reviews",
+            )
+            .await
+            {
+                tracing::error!("Failed to flag submission {}: {}", submission_id, e);
+            }
+        }
+    }
+    if let Some(error) = &error {
+        tracing::warn!("Submission {} failed: {}", submission_id, error);
+    }
+    if reviewer_disagreement {
+        tracing::warn!(
+            "Reviewers of {} split between accept and reject",
+            submission_id
+        );
+        let flags = if needs_human_review {
+            &["reviewer_disagreement", "needs_human_review"][..]
+        } else {
+            &["reviewer_disagreement"][..]
+        };
+        for flag in flags {
+            if let Err(e) =
+                crate::db::add_submission_flag(state.pool.as_ref(), submission_id, flag).await
+            {
+                tracing::error!("Failed to flag submission {}: {}", submission_id, e);
+            }
+        }
+    }
+
+    state.status_cache.invalidate(submission_id);
+    crate::events::publish(
+        &state.events,
+        crate::events::SubmissionEvent::Finished {
+            submission_id: submission_id.to_string(),
+            all_accepted,
+        },
+    );
+
     Ok(())
 }
 
+/// Generates and stores the submission's certificate (and its image when
+/// `CERTIFICATE_FORMAT=png`), returning the certificate's file name.
+pub(crate) async fn write_certificate(
+    state: &AppState,
+    submission_id: &str,
+    paper_title: &str,
+    kind: crate::pdf::CertificateKind,
+    issued_at: chrono::DateTime<chrono::Utc>,
+) -> Result<String, String> {
+    let cert_name = format!("{}_certificate.pdf", submission_id);
+    let cert_dir =
+        crate::storage::submission_results_dir(&state.config.results_folder, submission_id);
+    let cert_path = cert_dir.join(&cert_name);
+    let expires_at = state
+        .config
+        .certificate_validity_days
+        .map(|days| issued_at + chrono::Duration::days(days));
+    let pdf =
+        crate::pdf::generate_certificate(paper_title, submission_id, kind, issued_at, expires_at)?;
+    crate::storage::create_dir(&cert_dir, state.config.dir_mode)
+        .and_then(|()| {
+            crate::storage::write_atomic(
+                &state.config.temp_folder,
+                &cert_path,
+                &pdf,
+                state.config.file_mode,
+            )
+        })
+        .map_err(|e| e.to_string())?;
+    state.storage_usage.add(pdf.len() as u64);
+    // A cached image of an earlier certificate is stale now
+    let _ = std::fs::remove_file(cert_path.with_extension("png"));
+    if state.config.certificate_format == CertificateFormat::Png {
+        if let Err(e) = ensure_certificate_image(state, &cert_path).await {
+            tracing::warn!(
+                "Certificate image rendering failed for {}: {}",
+                submission_id,
+                e
+            );
+        }
+    }
+    Ok(cert_name)
+}
+
+/// The latest successful review from each reviewer.
+async fn latest_reviews(state: &AppState, submission_id: &str) -> Vec<ReviewResult> {
+    let rows = match crate::db::list_review_results(
+        state.pool.as_ref(),
+        submission_id,
+        None,
+        false,
+        i64::MAX,
+        0,
+    )
+    .await
+    {
+        Ok(rows) => rows,
+        Err(e) => {
+            tracing::error!("Failed to load reviews for {}: {}", submission_id, e);
+            return Vec::new();
+        }
+    };
+    // Rows are ordered by reviewer then creation, so later rows replace earlier
+    let mut latest: Vec<ReviewResult> = Vec::new();
+    for row in rows.into_iter().filter(|r| r.decision != "ERROR") {
+        match latest.last_mut() {
+            Some(last) if last.reviewer_name == row.reviewer_name => *last = row,
+            _ => latest.push(row),
+        }
+    }
+    latest
+}
+
+/// Asks Claude for the author-facing summary; failures only cost the summary.
+async fn generate_author_summary(
+    state: &AppState,
+    submission_id: &str,
+    outcome: &str,
+    reviews: &[ReviewResult],
+) -> Option<String> {
+    if reviews.is_empty() {
+        return None;
+    }
+    let inputs: Vec<(&str, &str)> = reviews
+        .iter()
+        .map(|r| (r.decision.as_str(), r.summary.as_deref().unwrap_or("")))
+        .collect();
+    let mut agent = crate::agents::ClaudeAgent::new(&state.config, state.http.clone());
+    let summary = agent.generate_author_summary(outcome, &inputs).await;
+    if state.config.store_token_usage {
+        let usage = agent.token_usage();
+        if let Err(e) = crate::db::add_submission_token_usage(
+            state.pool.as_ref(),
+            submission_id,
+            usage.input_tokens,
+            usage.output_tokens,
+        )
+        .await
+        {
+            tracing::warn!("Failed to record token usage for {}: {}", submission_id, e);
+        }
+    }
+    match summary {
+        Ok(summary) => Some(summary),
+        Err(e) => {
+            tracing::warn!("Author summary failed for {}: {}", submission_id, e);
+            None
+        }
+    }
+}
+
+/// PNG rendering of the certificate at `pdf_path`, stored beside it; made on
+/// first use unless `CERTIFICATE_FORMAT=png` rendered it at issuance.
+pub(crate) async fn ensure_certificate_image(
+    state: &AppState,
+    pdf_path: &std::path::Path,
+) -> Result<std::path::PathBuf, String> {
+    let image_path = pdf_path.with_extension("png");
+    if !image_path.exists() {
+        crate::pdf::render_first_page(
+            &state.config.pdftoppm_path,
+            pdf_path,
+            crate::pdf::CERTIFICATE_IMAGE_WIDTH,
+            &state.config.temp_folder,
+            &image_path,
+            state.config.file_mode,
+        )
+        .await?;
+        if let Ok(meta) = std::fs::metadata(&image_path) {
+            state.storage_usage.add(meta.len());
+        }
+    }
+    Ok(image_path)
+}
+
+/// The `?warning=` notices `results_location` attached to the redirect.
+fn upload_warnings(params: &[(String, String)]) -> Vec<&str> {
+    params
+        .iter()
+        .filter(|(key, _)| key == "warning")
+        .map(|(_, value)| value.as_str())
+        .collect()
+}
+
 pub async fn view_results(
     State(state): State<Arc<AppState>>,
     Path(submission_id): Path<String>,
+    Query(auth): Query<AuthorTokenQuery>,
+    Query(params): Query<Vec<(String, String)>>,
+    headers: HeaderMap,
 ) -> impl IntoResponse {
     let submission = match get_submission(state.pool.as_ref(), &submission_id).await {
-        Ok(Some(s)) => s,
+        Ok(Some(s)) if in_tenant(&state, &headers, &s) => s,
         _ => return Redirect::to("/").into_response(),
     };
+    let token = request_author_token(auth.token.as_deref(), &headers);
+    if !may_view(&state, &submission, token) {
+        return Redirect::to("/").into_response();
+    }
+    if submission_expired(&submission) {
+        return expired_response(&submission);
+    }
+    // Only a valid token is echoed back into links on the page
+    let token_query = match token {
+        Some(t)
+            if submission.author_token_hash.is_some()
+                && author_token_matches(&submission, Some(t)) =>
+        {
+            format!("?token={}", t)
+        }
+        _ => String::new(),
+    };
 
     let results = match get_review_results(state.pool.as_ref(), &submission_id).await {
         Ok(r) => r,
         Err(_) => return Redirect::to("/").into_response(),
     };
 
+    // Only failed reviews can be re-run, and only once processing has finished
+    let retryable: HashMap<&str, bool> = results
+        .iter()
+        .map(|(reviewer, r)| {
+            (
+                reviewer.as_str(),
+                submission.processing_complete && r.decision == "ERROR",
+            )
+        })
+        .collect();
+
+    let previous_versions = crate::db::list_previous_versions(state.pool.as_ref(), &submission_id)
+        .await
+        .unwrap_or_default();
+    let revisions = crate::db::list_revisions(state.pool.as_ref(), &submission_id)
+        .await
+        .unwrap_or_default();
+
+    // Rendered reviews per reviewer; empty under the plain format
+    let review_html: HashMap<&str, String> = results
+        .iter()
+        .map(|(reviewer, r)| {
+            let html = match state.config.review_format {
+                ReviewFormat::Markdown if r.decision != "ERROR" => {
+                    crate::markdown::render_markdown(&r.full_review)
+                }
+                _ => String::new(),
+            };
+            (reviewer.as_str(), html)
+        })
+        .collect();
+
     let mut ctx = Context::new();
     ctx.insert("submission_id", &submission_id);
     ctx.insert("results", &results);
+    let model_downgraded = results.values().any(|r| r.model_downgraded);
+    ctx.insert(
+        "downgrade_banner",
+        &Some(&state.config.downgrade_banner).filter(|b| model_downgraded && !b.is_empty()),
+    );
+    ctx.insert("review_html", &review_html);
+    ctx.insert("previous_versions", &previous_versions);
+    ctx.insert("revisions", &revisions);
+    ctx.insert("retryable", &retryable);
+    ctx.insert(
+        "branding",
+        state.config.branding(submission.tenant_id.as_deref()),
+    );
+    ctx.insert("token_query", &token_query);
     ctx.insert("all_accepted", &submission.all_accepted);
     ctx.insert("has_accepted", &submission.all_accepted);
     ctx.insert(
@@ -209,74 +1787,766 @@ pub async fn view_results(
         &submission.certificate_filename.unwrap_or_default(),
     );
     ctx.insert("processing", &!submission.processing_complete);
+    ctx.insert("category", &submission.category);
+    ctx.insert("certificate_kind", &submission.certificate_kind);
+    ctx.insert(
+        "submitted_at",
+        &submission
+            .created_at
+            .format("%Y-%m-%d %H:%M UTC")
+            .to_string(),
+    );
+    ctx.insert(
+        "expires_at",
+        &submission
+            .expires_at
+            .map(|at| at.format("%Y-%m-%d %H:%M UTC").to_string()),
+    );
+    ctx.insert("author_summary", &submission.author_summary);
+    ctx.insert("needs_human_review", &submission.needs_human_review);
+    ctx.insert(
+        "low_confidence",
+        &submission
+            .decision_confidence
+            .is_some_and(|c| c < crate::agents::LOW_CONFIDENCE),
+    );
+    ctx.insert("low_confidence_below", &crate::agents::LOW_CONFIDENCE);
+    ctx.insert("review_error", &submission.error);
+    ctx.insert("upload_warnings", &upload_warnings(&params));
+    ctx.insert(
+        "certificate_error",
+        &submission
+            .certificate_error
+            .map(|e| state.config.certificate_error_message.clone().unwrap_or(e)),
+    );
+
+    render_template(&state, "results.html", ctx)
+        .await
+        .into_response()
+}
+
+/// Longest a `?wait=` status request is held open.
+const MAX_STATUS_WAIT_SECS: u64 = 30;
 
-    render_template(&state, "results.html", ctx).await
+#[derive(serde::Deserialize)]
+pub struct StatusQuery {
+    wait: Option<u64>,
+    token: Option<String>,
 }
 
+/// With `?wait=<secs>` a request for an unfinished submission is held until
+/// its next review completes or it finishes, or until the wait (capped at
+/// `MAX_STATUS_WAIT_SECS`) runs out, and then reports the current state.
 pub async fn check_status(
     State(state): State<Arc<AppState>>,
     Path(submission_id): Path<String>,
+    Query(query): Query<StatusQuery>,
+    headers: HeaderMap,
 ) -> impl IntoResponse {
-    let submission = match get_submission(state.pool.as_ref(), &submission_id).await {
-        Ok(Some(s)) => s,
-        Ok(None) => {
-            return axum::Json(serde_json::json!({
-                "status": "not_found",
-                "message": "Review not found."
-            }))
-            .into_response()
-        }
-        Err(_) => {
-            return axum::Json(serde_json::json!({
-                "status": "error",
-                "message": "Database error."
-            }))
-            .into_response()
+    if let Some(wait) = query.wait.filter(|w| *w > 0) {
+        // Subscribe before reading so a change in between is not missed
+        let mut events = state.events.subscribe();
+        let pending = match state.status_cache.get(&submission_id) {
+            Some(cached) => !cached.submission.processing_complete,
+            None => matches!(
+                get_submission(state.pool.as_ref(), &submission_id).await,
+                Ok(Some(ref s)) if !s.processing_complete
+            ),
+        };
+        if pending {
+            let wait = std::time::Duration::from_secs(wait.min(MAX_STATUS_WAIT_SECS));
+            let _ = tokio::time::timeout(wait, async {
+                loop {
+                    match events.recv().await {
+                        Ok(event) if event.submission_id() == submission_id => break,
+                        Ok(_) => continue,
+                        // Missed events may include ours; report now
+                        Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => break,
+                        Err(tokio::sync::broadcast::error::RecvError::Closed) => {
+                            std::future::pending::<()>().await
+                        }
+                    }
+                }
+            })
+            .await;
         }
-    };
+    }
 
-    if !submission.processing_complete {
+    let cached = state.status_cache.get(&submission_id);
+    let submission = match &cached {
+        Some(cached) => cached.submission.clone(),
+        None => match get_submission(state.pool.as_ref(), &submission_id).await {
+            Ok(Some(s)) => s,
+            Ok(None) => {
+                return axum::Json(serde_json::json!({
+                    "status": "not_found",
+                    "message": "Review not found."
+                }))
+                .into_response()
+            }
+            Err(_) => {
+                return axum::Json(serde_json::json!({
+                    "status": "error",
+                    "message": "Database error."
+                }))
+                .into_response()
+            }
+        },
+    };
+    if !in_tenant(&state, &headers, &submission) {
         return axum::Json(serde_json::json!({
-            "status": "processing",
-            "message": "Review is still being processed."
+            "status": "not_found",
+            "message": "Review not found."
         }))
         .into_response();
     }
+    if submission_expired(&submission) {
+        state.status_cache.invalidate(&submission_id);
+        return (
+            StatusCode::GONE,
+            axum::Json(serde_json::json!({
+                "status": "expired",
+                "message": "The results for this submission have expired."
+            })),
+        )
+            .into_response();
+    }
+    if !may_view(
+        &state,
+        &submission,
+        request_author_token(query.token.as_deref(), &headers),
+    ) {
+        return (
+            StatusCode::FORBIDDEN,
+            axum::Json(serde_json::json!({
+                "status": "forbidden",
+                "message": "A valid author token is required."
+            })),
+        )
+            .into_response();
+    }
+    let body = match cached {
+        Some(cached) => cached.body,
+        None => match load_status(&state, &submission).await {
+            Ok(body) => {
+                state.status_cache.insert(submission, body.clone());
+                body
+            }
+            Err(body) => return axum::Json(body).into_response(),
+        },
+    };
+    axum::Json(body).into_response()
+}
 
-    let results = match get_review_results(state.pool.as_ref(), &submission_id).await {
+/// Builds a submission's status body from the database; `Err` holds the
+/// response for a failed read, which is never cached.
+async fn load_status(
+    state: &AppState,
+    submission: &Submission,
+) -> Result<serde_json::Value, serde_json::Value> {
+    let submission_id = submission.submission_id.as_str();
+    if !submission.processing_complete {
+        let pool = state.pool.as_ref();
+        let completed = crate::db::count_review_results(pool, submission_id)
+            .await
+            .unwrap_or(0);
+        let started = crate::db::review_job_running(pool, submission_id)
+            .await
+            .unwrap_or(false);
+        return Ok(pending_body(
+            completed,
+            started,
+            state.config.reviewer_count,
+        ));
+    }
+
+    let results = match get_review_results(state.pool.as_ref(), submission_id).await {
         Ok(r) => r,
         Err(_) => {
-            return axum::Json(serde_json::json!({
+            return Err(serde_json::json!({
                 "status": "error",
                 "message": "Failed to load results."
             }))
-            .into_response()
         }
     };
+    Ok(complete_status(submission, &results))
+}
 
-    axum::Json(serde_json::json!({
-        "status": "complete",
+/// Status of an unfinished submission: `queued` until a worker picks it up or
+/// a review is stored, `processing` after that.
+pub(crate) fn pending_status(completed_reviews: i64, started: bool) -> &'static str {
+    if completed_reviews == 0 && !started {
+        "queued"
+    } else {
+        "processing"
+    }
+}
+
+/// Body of a status response for a submission still under review.
+fn pending_body(completed: i64, started: bool, total: usize) -> serde_json::Value {
+    let status = pending_status(completed, started);
+    serde_json::json!({
+        "status": status,
+        "message": if status == "queued" {
+            "Review is queued and will start shortly."
+        } else {
+            "Review is still being processed."
+        },
+        "completed": completed,
+        "total": total
+    })
+}
+
+/// Body of a `complete` status response, shared with synchronous submits.
+pub(crate) fn complete_status(
+    submission: &Submission,
+    results: &HashMap<String, ReviewResultDisplay>,
+) -> serde_json::Value {
+    serde_json::json!({
+        "status": if submission.error.is_some() { "failed" } else { "complete" },
+        "error": submission.error,
+        "submission_id": submission.submission_id,
+        "created_at": submission.created_at,
         "results": results,
+        "model_downgraded": results.values().any(|r| r.model_downgraded),
         "all_accepted": submission.all_accepted,
-        "certificate_filename": submission.certificate_filename
-    }))
-    .into_response()
+        "certificate_filename": submission.certificate_filename,
+        "certificate_kind": submission.certificate_kind,
+        "certificate_error": submission.certificate_error,
+        "reviewer_disagreement": submission.reviewer_disagreement,
+        "needs_human_review": submission.needs_human_review,
+        "decision_confidence": submission.decision_confidence,
+        "low_confidence": submission
+            .decision_confidence
+            .is_some_and(|c| c < crate::agents::LOW_CONFIDENCE),
+        "parent_submission_id": submission.parent_submission_id
+    })
 }
 
+#[derive(serde::Deserialize)]
+pub struct RetryQuery {
+    token: Option<String>,
+    /// Model to start the re-run on instead of the default first choice.
+    model: Option<String>,
+    /// Generate a fresh review even when `REVIEW_CACHE` holds one.
+    #[serde(default)]
+    refresh: bool,
+}
+
+/// Re-runs one reviewer, replaces its stored review and re-decides the
+/// submission's certificate.
 pub async fn retry_review(
     State(state): State<Arc<AppState>>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
     Path((submission_id, reviewer_name)): Path<(String, String)>,
-) -> impl IntoResponse {
+    Query(query): Query<RetryQuery>,
+    headers: HeaderMap,
+) -> axum::response::Response {
+    let actor = audit::actor(&state, &headers, addr);
+    let response = run_retry(
+        state.clone(),
+        submission_id.clone(),
+        reviewer_name.clone(),
+        query,
+        headers,
+    )
+    .await;
+    let outcome = if response.status().is_success() {
+        audit::SUCCESS
+    } else {
+        audit::FAILURE
+    };
+    let detail = format!("{} ({})", reviewer_name, response.status().as_u16());
+    audit::record(
+        &state,
+        "retry",
+        Some(&submission_id),
+        &actor,
+        outcome,
+        Some(&detail),
+    )
+    .await;
+    response
+}
+
+async fn run_retry(
+    state: Arc<AppState>,
+    submission_id: String,
+    reviewer_name: String,
+    query: RetryQuery,
+    headers: HeaderMap,
+) -> axum::response::Response {
+    let retry_error = |status: StatusCode, error: String| {
+        (
+            status,
+            axum::Json(serde_json::json!({
+                "success": false,
+                "error": error
+            })),
+        )
+            .into_response()
+    };
+
+    if state.maintenance.is_enabled() {
+        return retry_error(
+            StatusCode::SERVICE_UNAVAILABLE,
+            MAINTENANCE_MESSAGE.to_string(),
+        );
+    }
+
+    let submission = match get_submission(state.pool.as_ref(), &submission_id).await {
+        Ok(Some(s)) if in_tenant(&state, &headers, &s) => s,
+        _ => return retry_error(StatusCode::NOT_FOUND, "Submission not found.".to_string()),
+    };
+    if !author_token_matches(
+        &submission,
+        request_author_token(query.token.as_deref(), &headers),
+    ) {
+        return retry_error(
+            StatusCode::FORBIDDEN,
+            "A valid author token is required.".to_string(),
+        );
+    }
+
+    let tenant = submission.tenant_id.as_deref();
+    let reviewers = state.config.reviewer_labels(tenant);
+    if !reviewers.contains(&reviewer_name) {
+        return retry_error(
+            StatusCode::NOT_FOUND,
+            format!("Unknown reviewer '{}'.", reviewer_name),
+        );
+    }
+    let model = query
+        .model
+        .as_deref()
+        .map(str::trim)
+        .filter(|m| !m.is_empty());
+    if let Some(model) = model {
+        if !crate::agents::CLAUDE_MODELS.contains(&model) {
+            return retry_error(
+                StatusCode::BAD_REQUEST,
+                format!(
+                    "Unknown model '{}'. Choose one of: {}.",
+                    model,
+                    crate::agents::CLAUDE_MODELS.join(", ")
+                ),
+            );
+        }
+    }
+
+    let Some(_lock) = state.submission_locks.try_lock(&submission_id) else {
+        return retry_error(
+            StatusCode::CONFLICT,
+            "This submission is already being updated; try again shortly.".to_string(),
+        );
+    };
+
+    let paper_data = match tokio::fs::read(&submission.file_path).await {
+        Ok(data) => data,
+        Err(e) => {
+            tracing::error!("Failed to read upload for {}: {}", submission_id, e);
+            return retry_error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "The uploaded paper is no longer available.".to_string(),
+            );
+        }
+    };
+    let max_pages = state.config.max_pdf_pages;
+    let extracted = state
+        .extraction_pool
+        .run(move || {
+            let extracted = crate::agents::extract_text_from_pdf(&paper_data, max_pages);
+            (paper_data, extracted)
+        })
+        .await;
+    let paper_text = match extracted {
+        Ok((paper_data, Ok(extracted))) => {
+            reextract_if_garbled(&state, extracted, &paper_data)
+                .await
+                .text
+        }
+        Ok((_, Err(e))) => return retry_error(StatusCode::UNPROCESSABLE_ENTITY, e.to_string()),
+        Err(_) => {
+            return retry_error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "PDF extraction failed.".to_string(),
+            )
+        }
+    };
+
+    // The earlier review stays current until its replacement is stored
+    let model = model.or_else(|| state.config.reviewer_model(tenant, &reviewer_name));
+    let outcome = run_reviewer(
+        &state,
+        &submission_id,
+        &reviewer_name,
+        &paper_text,
+        model,
+        None,
+        !query.refresh,
+    )
+    .await;
+    if let Some(review_id) = outcome.review_id {
+        if let Err(e) = crate::db::supersede_review_results(
+            state.pool.as_ref(),
+            &submission_id,
+            &reviewer_name,
+            review_id,
+        )
+        .await
+        {
+            tracing::error!(
+                "Failed to supersede replaced review for {}: {}",
+                submission_id,
+                e
+            );
+        }
+    }
+
+    let rows = match crate::db::list_review_results(
+        state.pool.as_ref(),
+        &submission_id,
+        None,
+        false,
+        i64::MAX,
+        0,
+    )
+    .await
+    {
+        Ok(rows) => rows,
+        Err(e) => {
+            tracing::error!("Failed to load reviews for {}: {}", submission_id, e);
+            return retry_error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to load reviews.".to_string(),
+            );
+        }
+    };
+    let mut all_accepted = true;
+    let mut all_minor_or_better = true;
+    for name in &reviewers {
+        let (accepted, minor_or_better) = if *name == reviewer_name {
+            (outcome.accepted, outcome.minor_or_better)
+        } else {
+            match rows.iter().find(|r| r.reviewer_name == *name) {
+                Some(r) => (r.decision == "ACCEPTED", stored_minor_or_better(r)),
+                None => (false, false),
+            }
+        };
+        all_accepted &= accepted;
+        all_minor_or_better &= minor_or_better;
+    }
+
+    let paper_title = submission
+        .paper_title
+        .clone()
+        .or_else(|| submission.filename.clone())
+        .unwrap_or_default();
+    if let Err(e) = finalize_submission(
+        &state,
+        &submission_id,
+        &paper_title,
+        tenant,
+        all_accepted,
+        all_minor_or_better,
+    )
+    .await
+    {
+        tracing::error!(
+            "Failed to update submission {} after retry: {}",
+            submission_id,
+            e
+        );
+        return retry_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Failed to update submission.".to_string(),
+        );
+    }
+
     axum::Json(serde_json::json!({
-        "success": false,
-        "error": "Retry not yet implemented in Rust version"
+        "success": true,
+        "reviewer_name": reviewer_name,
+        "model_used": outcome.model_used
     }))
+    .into_response()
 }
 
-async fn render_template(state: &AppState, name: &str, ctx: Context) -> Html<String> {
+/// Whether a stored review is ACCEPTED or ACCEPTED WITH MINOR REVISION.
+/// Minor revisions are only recognisable from the structured review, as
+/// both revision outcomes are stored as REVISION.
+fn stored_minor_or_better(review: &crate::db::ReviewResult) -> bool {
+    review.decision == "ACCEPTED"
+        || (review.decision == "REVISION"
+            && review
+                .structured_review
+                .as_ref()
+                .and_then(|s| s.get("decision"))
+                .and_then(|d| d.as_str())
+                .is_some_and(|d| {
+                    d.trim()
+                        .eq_ignore_ascii_case("ACCEPTED WITH MINOR REVISION REQUIRED")
+                }))
+}
+
+async fn render_template(_state: &AppState, name: &str, ctx: Context) -> Html<String> {
     let tera = crate::templates::get_tera();
     let rendered = tera
         .render(name, &ctx)
         .unwrap_or_else(|_| format!("Template error: {}", name));
     Html(rendered)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tower::ServiceExt;
+
+    /// Status `read_upload_form` answers for `body` behind a 1 KiB body limit.
+    async fn upload_form_status(body: impl Into<String>) -> StatusCode {
+        let app = axum::Router::new()
+            .route(
+                "/",
+                axum::routing::post(|mut multipart: axum::extract::Multipart| async move {
+                    match read_upload_form(&mut multipart, 1024).await {
+                        Ok(_) => StatusCode::OK,
+                        Err(rejection) => rejection.status,
+                    }
+                }),
+            )
+            .layer(axum::extract::DefaultBodyLimit::max(1024));
+        let request = axum::http::Request::post("/")
+            .header("content-type", "multipart/form-data; boundary=X")
+            .body(axum::body::Body::from(body.into()))
+            .unwrap();
+        app.oneshot(request).await.unwrap().status()
+    }
+
+    /// Posts `pdf` with `title` through the upload form and returns the
+    /// status and page the browser gets back.
+    async fn post_upload(
+        config: crate::config::Config,
+        title: &str,
+        pdf: Vec<u8>,
+    ) -> (StatusCode, String) {
+        let app = axum::Router::new()
+            .route("/upload", axum::routing::post(upload_handler))
+            .with_state(crate::test_support::app_state(config));
+        let mut body = format!(
+            "--X\r\nContent-Disposition: form-data; name=\"paper_title\"\r\n\r\n{}\r\n\
+             --X\r\nContent-Disposition: form-data; name=\"paper\"; filename=\"paper.pdf\"\r\n\
+             Content-Type: application/pdf\r\n\r\n",
+            title
+        )
+        .into_bytes();
+        body.extend_from_slice(&pdf);
+        body.extend_from_slice(b"\r\n--X--\r\n");
+        let mut request = axum::http::Request::post("/upload")
+            .header("content-type", "multipart/form-data; boundary=X")
+            .body(axum::body::Body::from(body))
+            .unwrap();
+        request
+            .extensions_mut()
+            .insert(ConnectInfo(SocketAddr::from(([127, 0, 0, 1], 4000))));
+        let response = app.oneshot(request).await.unwrap();
+        let status = response.status();
+        let page = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, String::from_utf8_lossy(&page).into_owned())
+    }
+
+    /// Settings under which an acceptable upload is refused at the storage
+    /// quota, the last check before anything is written.
+    fn quota_config() -> crate::config::Config {
+        let mut config = crate::test_support::config();
+        config.duplicate_title_policy = DuplicateTitlePolicy::Off;
+        config.storage_quota_bytes = Some(1);
+        config
+    }
+
+    #[tokio::test]
+    async fn blank_title_is_refused_on_the_form_only_when_required() {
+        let mut config = quota_config();
+        config.require_title = true;
+        let (status, page) = post_upload(config, " ", crate::test_support::english_pdf()).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(page.contains("A paper title is required."), "{}", page);
+        assert!(page.contains("id=\"reviewForm\""));
+
+        let mut config = quota_config();
+        config.require_title = false;
+        let (status, page) = post_upload(config, " ", crate::test_support::english_pdf()).await;
+        assert_eq!(status, StatusCode::INSUFFICIENT_STORAGE);
+        assert!(!page.contains("A paper title is required."));
+    }
+
+    #[tokio::test]
+    async fn full_storage_is_reported_on_the_form() {
+        let (status, page) = post_upload(
+            quota_config(),
+            "On Proofs",
+            crate::test_support::english_pdf(),
+        )
+        .await;
+        assert_eq!(status, StatusCode::INSUFFICIENT_STORAGE);
+        assert!(page.contains("The server is out of storage for new submissions."));
+        assert!(page.contains("id=\"reviewForm\""));
+    }
+
+    #[tokio::test]
+    async fn encrypted_pdf_is_refused_on_the_form() {
+        let (status, page) = post_upload(
+            crate::test_support::config(),
+            "On Proofs",
+            crate::test_support::encrypted_pdf(),
+        )
+        .await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert!(page.contains("This PDF is password-protected; please upload an unprotected copy."));
+    }
+
+    #[tokio::test]
+    async fn short_paper_is_refused_on_the_form() {
+        let mut config = crate::test_support::config();
+        config.min_page_count = Some(2);
+        let (status, page) =
+            post_upload(config, "On Proofs", crate::test_support::english_pdf()).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(page.contains("Papers must be at least 2 pages; this one has 1."));
+        assert!(page.contains("id=\"reviewForm\""));
+    }
+
+    #[tokio::test]
+    async fn unsupported_language_is_refused_on_the_form() {
+        let mut config = crate::test_support::config();
+        config.allowed_languages = vec!["de".to_string()];
+        config.language_policy = LanguagePolicy::Reject;
+        let (status, page) =
+            post_upload(config, "On Proofs", crate::test_support::english_pdf()).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert!(page.contains("Papers must be written in one of: de."));
+        assert!(page.contains("id=\"reviewForm\""));
+    }
+
+    #[tokio::test]
+    async fn active_content_is_refused_on_the_form() {
+        let mut config = crate::test_support::config();
+        config.active_content_policy = ActiveContentPolicy::Reject;
+        let pdf = crate::test_support::pdf(
+            &[
+                "<< /Type /Catalog /Pages 2 0 R /OpenAction << /S /JavaScript /JS (app.alert) >> >>"
+                    .to_string(),
+                "<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_string(),
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 612 792] >>".to_string(),
+            ],
+            "",
+        );
+        let (status, page) = post_upload(config, "On Proofs", pdf).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert!(page.contains("This PDF contains active content (javascript, open_action)"));
+        assert!(page.contains("id=\"reviewForm\""));
+    }
+
+    fn paper_field(data: &str) -> String {
+        format!(
+            "--X\r\nContent-Disposition: form-data; name=\"paper\"; filename=\"p.pdf\"\r\n\r\n{}\r\n--X--\r\n",
+            data
+        )
+    }
+
+    #[test]
+    fn long_title_is_truncated_on_a_char_boundary() {
+        let title = "\u{e9}".repeat(20);
+        let truncated = truncate_title(&title, 10);
+        assert_eq!(truncated, format!("{}...", "\u{e9}".repeat(7)));
+        assert_eq!(truncated.chars().count(), 10);
+        assert_eq!(
+            truncate_title(&"\u{e9}".repeat(10), 10),
+            "\u{e9}".repeat(10)
+        );
+    }
+
+    #[test]
+    fn long_title_is_refused_under_reject_policy() {
+        let title = "\u{e9}".repeat(11);
+        let rejection = check_title_length(&title, 10, TitleLengthPolicy::Reject)
+            .err()
+            .unwrap();
+        assert_eq!(rejection.status, StatusCode::BAD_REQUEST);
+        assert_eq!(
+            rejection.message,
+            "The paper title must be at most 10 characters."
+        );
+        assert!(check_title_length(&"\u{e9}".repeat(10), 10, TitleLengthPolicy::Reject).is_ok());
+        assert!(check_title_length(&title, 10, TitleLengthPolicy::Truncate).is_ok());
+    }
+
+    #[tokio::test]
+    async fn long_title_is_refused_on_the_form_under_reject_policy() {
+        let mut config = crate::test_support::config();
+        config.title_max_chars = 10;
+        config.title_length_policy = TitleLengthPolicy::Reject;
+        let (status, page) = post_upload(
+            config,
+            "On Proofs and Refutations",
+            crate::test_support::english_pdf(),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(page.contains("The paper title must be at most 10 characters."));
+        assert!(page.contains("id=\"reviewForm\""));
+    }
+
+    #[test]
+    fn upload_warnings_survive_the_redirect_to_results() {
+        let created = CreatedSubmission {
+            submission_id: "abc".to_string(),
+            author_token: "tok".to_string(),
+            warnings: vec![
+                "A paper with the same title was already submitted (x1); this looks like a resubmission."
+                    .to_string(),
+            ],
+        };
+        let uri: axum::http::Uri = results_location(&created).parse().unwrap();
+        assert_eq!(uri.path(), "/results/abc");
+        let Query(params) = Query::<Vec<(String, String)>>::try_from_uri(&uri).unwrap();
+        assert_eq!(upload_warnings(&params), created.warnings);
+        let Query(auth) = Query::<AuthorTokenQuery>::try_from_uri(&uri).unwrap();
+        assert_eq!(auth.token.as_deref(), Some("tok"));
+    }
+
+    #[test]
+    fn unstarted_submission_is_queued_until_a_worker_claims_it() {
+        // No stored reviews and no running job: the job is still unclaimed
+        let body = pending_body(0, false, 3);
+        assert_eq!(body["status"], "queued");
+        assert_eq!(body["message"], "Review is queued and will start shortly.");
+        assert_eq!(body["completed"], 0);
+        assert_eq!(body["total"], 3);
+
+        let body = pending_body(0, true, 3);
+        assert_eq!(body["status"], "processing");
+        assert_eq!(body["message"], "Review is still being processed.");
+        assert_eq!(pending_body(1, false, 3)["status"], "processing");
+    }
+
+    #[tokio::test]
+    async fn upload_form_rejects_empty_malformed_and_oversized_bodies() {
+        assert_eq!(
+            upload_form_status(paper_field("%PDF-1.4")).await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            upload_form_status("--X--\r\n").await,
+            StatusCode::BAD_REQUEST
+        );
+        assert_eq!(
+            upload_form_status("not a multipart body").await,
+            StatusCode::BAD_REQUEST
+        );
+        assert_eq!(
+            upload_form_status(paper_field(&"a".repeat(4096))).await,
+            StatusCode::PAYLOAD_TOO_LARGE
+        );
+    }
+}
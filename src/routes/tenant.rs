@@ -0,0 +1,36 @@
+// Multi-tenant hosting (TENANT_SOURCE): each request names a venue, and
+// submissions are only visible within the venue they were made in.
+use axum::{
+    extract::{Request, State},
+    http::{HeaderMap, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::sync::Arc;
+
+use crate::db::Submission;
+use crate::state::AppState;
+
+/// The configured tenant the request names; `None` when multi-tenancy is off.
+pub(crate) fn request_tenant<'a>(state: &'a AppState, headers: &HeaderMap) -> Option<&'a str> {
+    state.config.tenancy.as_ref()?.resolve(headers)
+}
+
+/// False for a submission made under a different tenant than the request's.
+/// Callers answer as if the submission did not exist.
+pub(crate) fn in_tenant(state: &AppState, headers: &HeaderMap, submission: &Submission) -> bool {
+    state.config.tenancy.is_none()
+        || submission.tenant_id.as_deref() == request_tenant(state, headers)
+}
+
+/// Refuses public requests that name no configured tenant with a 404.
+pub async fn require_tenant(
+    State(state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if state.config.tenancy.is_some() && request_tenant(&state, request.headers()).is_none() {
+        return (StatusCode::NOT_FOUND, "Unknown venue").into_response();
+    }
+    next.run(request).await
+}
@@ -0,0 +1,101 @@
+// Live feed of submission events over WebSocket: the operator-wide feed sits
+// with the management routes, the public one follows a single submission for
+// its author.
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        Query, State,
+    },
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+};
+use serde::Deserialize;
+use std::sync::Arc;
+use tokio::sync::broadcast;
+
+use super::pages::{author_token_matches, request_author_token};
+use super::tenant::in_tenant;
+use crate::events::SubmissionEvent;
+use crate::state::AppState;
+
+#[derive(Deserialize)]
+pub struct WsQuery {
+    submission_id: Option<String>,
+    token: Option<String>,
+}
+
+/// Upgrades to a WebSocket streaming every `SubmissionEvent` as JSON text
+/// messages, optionally only those for `?submission_id=`.
+pub async fn ws_operator_events(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<WsQuery>,
+    ws: WebSocketUpgrade,
+) -> Response {
+    let events = state.events.subscribe();
+    ws.on_upgrade(move |socket| stream_events(socket, events, query.submission_id))
+}
+
+/// Upgrades to a WebSocket streaming the events of `?submission_id=`; the
+/// submission's author token is required as `?token=` or `X-Author-Token`.
+pub async fn ws_submission_events(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<WsQuery>,
+    headers: HeaderMap,
+    ws: WebSocketUpgrade,
+) -> Response {
+    let Some(submission_id) = query.submission_id else {
+        return (StatusCode::BAD_REQUEST, "submission_id is required").into_response();
+    };
+    let submission = match crate::db::get_submission(state.pool.as_ref(), &submission_id).await {
+        Ok(Some(s)) if in_tenant(&state, &headers, &s) => s,
+        Ok(_) => return (StatusCode::NOT_FOUND, "Unknown submission").into_response(),
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+    };
+    let token = request_author_token(query.token.as_deref(), &headers);
+    if submission.author_token_hash.is_none() || !author_token_matches(&submission, token) {
+        return (StatusCode::FORBIDDEN, "A valid author token is required").into_response();
+    }
+
+    let events = state.events.subscribe();
+    ws.on_upgrade(move |socket| stream_events(socket, events, Some(submission_id)))
+}
+
+/// Forwards events until either side closes. Clients that fall behind skip
+/// the dropped events and receive a `lagged` notice instead.
+async fn stream_events(
+    mut socket: WebSocket,
+    mut events: broadcast::Receiver<SubmissionEvent>,
+    filter: Option<String>,
+) {
+    loop {
+        let message = tokio::select! {
+            incoming = socket.recv() => match incoming {
+                // Pings are answered by the socket itself; client data is ignored
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => continue,
+            },
+            event = events.recv() => match event {
+                Ok(event) => {
+                    if filter.as_deref().is_some_and(|id| id != event.submission_id()) {
+                        continue;
+                    }
+                    match serde_json::to_string(&event) {
+                        Ok(json) => Message::Text(json),
+                        Err(_) => continue,
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    let notice = serde_json::json!({ "event": "lagged", "skipped": skipped });
+                    Message::Text(notice.to_string())
+                }
+                Err(broadcast::error::RecvError::Closed) => {
+                    let _ = socket.send(Message::Close(None)).await;
+                    break;
+                }
+            },
+        };
+        if socket.send(message).await.is_err() {
+            break;
+        }
+    }
+}
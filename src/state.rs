@@ -1,9 +1,197 @@
 use crate::config::Config;
 use crate::db::DbPool;
+use crate::events::SubmissionEvent;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{Mutex, OwnedMutexGuard};
 
 #[derive(Clone)]
 pub struct AppState {
     pub pool: DbPool,
     pub config: Arc<Config>,
+    /// Shared client for Claude API calls so connections are pooled.
+    pub http: reqwest::Client,
+    /// Lifecycle events for the `/ws` live feed.
+    pub events: tokio::sync::broadcast::Sender<SubmissionEvent>,
+    pub submission_locks: SubmissionLocks,
+    /// Bytes used by uploads and results, for `STORAGE_QUOTA_BYTES`.
+    pub storage_usage: Arc<crate::storage::StorageUsage>,
+    pub in_flight_uploads: InFlightUploads,
+    pub maintenance: Arc<MaintenanceMode>,
+    /// Set when `BATCH_REVIEWS` routes reviews through the Message Batches API.
+    pub batches: Option<crate::agents::BatchCoordinator>,
+    pub review_queue: Arc<crate::queue::ReviewQueue>,
+    /// Recent `/api/status` responses; see [`StatusCache`].
+    pub status_cache: StatusCache,
+    /// Threads PDF text extraction runs on (`EXTRACTION_WORKERS`).
+    pub extraction_pool: crate::agents::ExtractionPool,
+}
+
+/// While enabled, uploads and review runs are refused with 503; results and
+/// downloads are still served.
+#[derive(Default)]
+pub struct MaintenanceMode {
+    enabled: AtomicBool,
+}
+
+impl MaintenanceMode {
+    pub fn new(enabled: bool) -> Self {
+        if enabled {
+            tracing::warn!("Starting in maintenance mode; new submissions are paused");
+        }
+        Self {
+            enabled: AtomicBool::new(enabled),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    /// Switches the mode, logging when it actually changes.
+    pub fn set(&self, enabled: bool) {
+        if self.enabled.swap(enabled, Ordering::SeqCst) != enabled {
+            if enabled {
+                tracing::warn!("Maintenance mode enabled; new submissions are paused");
+            } else {
+                tracing::info!("Maintenance mode disabled; accepting submissions");
+            }
+        }
+    }
+}
+
+/// Per-submission locks held while reviews are written or recomputed, so
+/// concurrent retries cannot interleave their updates.
+#[derive(Clone, Default)]
+pub struct SubmissionLocks {
+    locks: Arc<std::sync::Mutex<HashMap<String, Arc<Mutex<()>>>>>,
+}
+
+impl SubmissionLocks {
+    /// Takes the submission's lock, or returns `None` if another operation
+    /// holds it. The lock is released when the guard is dropped.
+    pub fn try_lock(&self, submission_id: &str) -> Option<OwnedMutexGuard<()>> {
+        let mut locks = self.locks.lock().unwrap_or_else(|e| e.into_inner());
+        // Entries referenced only by the map are unheld; drop them so the map
+        // doesn't grow with every submission
+        locks.retain(|_, lock| Arc::strong_count(lock) > 1);
+        locks
+            .entry(submission_id.to_string())
+            .or_default()
+            .clone()
+            .try_lock_owned()
+            .ok()
+    }
+}
+
+/// Uploads whose reviews are still running, keyed by content hash, so a
+/// re-upload of the same file joins the running submission instead of
+/// starting a second review set.
+#[derive(Clone, Default)]
+pub struct InFlightUploads {
+    entries: Arc<std::sync::Mutex<HashMap<String, InFlightUpload>>>,
+}
+
+#[derive(Clone)]
+pub struct InFlightUpload {
+    pub submission_id: String,
+    pub author_token: String,
+}
+
+impl InFlightUploads {
+    /// Registers `upload` under `content_hash`, or returns the upload already
+    /// registered for it. The entry is removed when the guard is dropped.
+    pub fn claim(
+        &self,
+        content_hash: &str,
+        upload: InFlightUpload,
+    ) -> Result<InFlightGuard, InFlightUpload> {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(existing) = entries.get(content_hash) {
+            return Err(existing.clone());
+        }
+        entries.insert(content_hash.to_string(), upload);
+        Ok(InFlightGuard {
+            uploads: self.clone(),
+            content_hash: content_hash.to_string(),
+        })
+    }
+}
+
+pub struct InFlightGuard {
+    uploads: InFlightUploads,
+    content_hash: String,
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        self.uploads
+            .entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&self.content_hash);
+    }
+}
+
+/// Short-lived copies of `/api/status` responses, so heavy polling of a
+/// processing submission doesn't query the database on every request.
+/// Writers invalidate a submission's entry whenever its status changes;
+/// the TTL bounds staleness from writes made by other instances.
+#[derive(Clone)]
+pub struct StatusCache {
+    ttl: Duration,
+    entries: Arc<std::sync::Mutex<HashMap<String, CachedStatus>>>,
+}
+
+#[derive(Clone)]
+pub struct CachedStatus {
+    /// Kept for the author-token check, which is never cached.
+    pub submission: crate::db::Submission,
+    pub body: serde_json::Value,
+    stored_at: Instant,
+}
+
+impl StatusCache {
+    /// A zero `ttl` disables caching.
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: Arc::default(),
+        }
+    }
+
+    pub fn get(&self, submission_id: &str) -> Option<CachedStatus> {
+        let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries
+            .get(submission_id)
+            .filter(|entry| entry.stored_at.elapsed() < self.ttl)
+            .cloned()
+    }
+
+    pub fn insert(&self, submission: crate::db::Submission, body: serde_json::Value) {
+        if self.ttl.is_zero() {
+            return;
+        }
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        // Expired entries are dropped here so the map only holds active polls
+        entries.retain(|_, entry| entry.stored_at.elapsed() < self.ttl);
+        entries.insert(
+            submission.submission_id.clone(),
+            CachedStatus {
+                submission,
+                body,
+                stored_at: Instant::now(),
+            },
+        );
+    }
+
+    /// Drops the submission's entry; call after every write to its status.
+    pub fn invalidate(&self, submission_id: &str) {
+        self.entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(submission_id);
+    }
 }
@@ -1,17 +1,294 @@
-use std::path::PathBuf;
-use uuid::Uuid;
 use chrono::Utc;
+use sha2::{Digest, Sha256};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use uuid::Uuid;
+
+use crate::config::UploadNaming;
 
 pub fn generate_submission_id() -> String {
     format!(
         "{}_{}",
         Utc::now().format("%Y%m%d"),
-        Uuid::new_v4().to_string()[..8].to_string()
+        &Uuid::new_v4().to_string()[..8]
     )
 }
 
-pub fn ensure_dirs(upload_folder: &PathBuf, results_folder: &PathBuf) -> std::io::Result<()> {
-    std::fs::create_dir_all(upload_folder)?;
-    std::fs::create_dir_all(results_folder)?;
+/// Reduces a client-supplied filename to a safe final path component:
+/// directories are dropped, characters outside `[A-Za-z0-9._-]` become `_`
+/// and leading dots are removed, so `../../evil .pdf` becomes `evil_.pdf`.
+pub fn sanitize_filename(filename: &str) -> String {
+    let base = filename.rsplit(['/', '\\']).next().unwrap_or("");
+    let cleaned: String = base
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let cleaned: String = cleaned.trim_start_matches('.').chars().take(120).collect();
+    if cleaned.is_empty() {
+        "paper.pdf".to_string()
+    } else {
+        cleaned
+    }
+}
+
+/// Name of the stored upload under `scheme`.
+pub fn upload_file_name(scheme: UploadNaming, submission_id: &str, filename: &str) -> String {
+    match scheme {
+        UploadNaming::IdAndFilename => {
+            format!("{}_{}", submission_id, sanitize_filename(filename))
+        }
+        UploadNaming::IdOnly => format!("{}.pdf", submission_id),
+    }
+}
+
+/// Secret handed to the author at submission time; only its hash is stored.
+pub fn generate_author_token() -> String {
+    format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple())
+}
+
+pub fn hash_author_token(token: &str) -> String {
+    format!("{:x}", Sha256::digest(token.as_bytes()))
+}
+
+/// Hex SHA-256 of an uploaded file, used to spot repeated uploads.
+pub fn content_hash(data: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(data))
+}
+
+pub fn ensure_dirs(
+    upload_folder: &Path,
+    results_folder: &Path,
+    temp_folder: &Path,
+    dir_mode: Option<u32>,
+) -> std::io::Result<()> {
+    create_dir(upload_folder, dir_mode)?;
+    create_dir(results_folder, dir_mode)?;
+    create_dir(temp_folder, dir_mode)?;
     Ok(())
 }
+
+/// Creates `path` and its parents, then applies `mode` (`DIR_MODE`) to
+/// `path` itself; without a mode the process umask decides.
+pub fn create_dir(path: &Path, mode: Option<u32>) -> std::io::Result<()> {
+    std::fs::create_dir_all(path)?;
+    set_mode(path, mode)
+}
+
+/// Sets the permission bits of `path` to `mode`, if given. A no-op on
+/// platforms without Unix permissions.
+pub fn set_mode(path: &Path, mode: Option<u32>) -> std::io::Result<()> {
+    #[cfg(unix)]
+    if let Some(mode) = mode {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(mode))?;
+    }
+    #[cfg(not(unix))]
+    let _ = (path, mode);
+    Ok(())
+}
+
+/// Directory holding one submission's generated artifacts.
+pub fn submission_results_dir(results_folder: &Path, submission_id: &str) -> PathBuf {
+    results_folder.join(submission_id)
+}
+
+/// Locates a submission artifact, falling back to the flat results layout
+/// used before per-submission directories.
+pub fn resolve_result_file(results_folder: &Path, submission_id: &str, filename: &str) -> PathBuf {
+    let path = submission_results_dir(results_folder, submission_id).join(filename);
+    if path.exists() {
+        path
+    } else {
+        results_folder.join(filename)
+    }
+}
+
+/// True when `filename` matches one of `patterns`, each an exact name or a
+/// name with a single `*` wildcard (e.g. `*_certificate.pdf`).
+pub fn download_allowed(patterns: &[String], filename: &str) -> bool {
+    patterns
+        .iter()
+        .any(|pattern| match pattern.split_once('*') {
+            Some((prefix, suffix)) => {
+                filename.len() >= prefix.len() + suffix.len()
+                    && filename.starts_with(prefix)
+                    && filename.ends_with(suffix)
+            }
+            None => pattern == filename,
+        })
+}
+
+/// Writes `contents` to a temp file in `temp_folder` and renames it onto
+/// `dest`, so readers never see a partially written file. `temp_folder`
+/// must be on the same filesystem as `dest` for the rename to be atomic.
+/// The file gets `mode` (`FILE_MODE`) when given, else the temp file's 0600.
+pub fn write_atomic(
+    temp_folder: &Path,
+    dest: &Path,
+    contents: &[u8],
+    mode: Option<u32>,
+) -> std::io::Result<()> {
+    let mut file = tempfile::NamedTempFile::new_in(temp_folder)?;
+    file.write_all(contents)?;
+    set_mode(file.path(), mode)?;
+    file.as_file().sync_all()?;
+    file.persist(dest).map_err(|e| e.error)?;
+    Ok(())
+}
+
+/// Running total of bytes stored under the upload and results folders,
+/// scanned once at startup and then updated as files are written.
+#[derive(Default)]
+pub struct StorageUsage {
+    bytes: AtomicU64,
+}
+
+impl StorageUsage {
+    pub fn scan(folders: &[&Path]) -> Self {
+        let bytes = folders.iter().map(|f| dir_size(f)).sum();
+        Self {
+            bytes: AtomicU64::new(bytes),
+        }
+    }
+
+    pub fn current(&self) -> u64 {
+        self.bytes.load(Ordering::Relaxed)
+    }
+
+    /// Reserves `len` bytes if the total stays within `quota`. Call
+    /// [`release`](Self::release) if the write then fails.
+    pub fn try_reserve(&self, len: u64, quota: u64) -> bool {
+        self.bytes
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |used| {
+                used.checked_add(len).filter(|total| *total <= quota)
+            })
+            .is_ok()
+    }
+
+    /// Records bytes written without a quota check (generated artifacts).
+    pub fn add(&self, len: u64) {
+        self.bytes.fetch_add(len, Ordering::SeqCst);
+    }
+
+    pub fn release(&self, len: u64) {
+        let _ = self
+            .bytes
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |used| {
+                Some(used.saturating_sub(len))
+            });
+    }
+}
+
+/// An upload's quota reservation and stored file, both undone on drop unless
+/// [`keep`](Self::keep) is called once the submission is safely recorded.
+pub struct PendingUpload<'a> {
+    usage: &'a StorageUsage,
+    len: u64,
+    path: Option<PathBuf>,
+}
+
+impl<'a> PendingUpload<'a> {
+    /// Takes over `len` bytes already reserved or added to `usage`.
+    pub fn new(usage: &'a StorageUsage, len: u64) -> Self {
+        Self {
+            usage,
+            len,
+            path: None,
+        }
+    }
+
+    /// Records the file written for the upload, so a drop removes it.
+    pub fn stored_at(&mut self, path: &Path) {
+        self.path = Some(path.to_path_buf());
+    }
+
+    pub fn keep(mut self) {
+        self.len = 0;
+        self.path = None;
+    }
+}
+
+impl Drop for PendingUpload<'_> {
+    fn drop(&mut self) {
+        if let Some(path) = &self.path {
+            if let Err(e) = std::fs::remove_file(path) {
+                tracing::warn!(
+                    "Failed to remove abandoned upload {}: {}",
+                    path.display(),
+                    e
+                );
+            }
+        }
+        if self.len > 0 {
+            self.usage.release(self.len);
+        }
+    }
+}
+
+pub(crate) fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|entry| match entry.metadata() {
+            Ok(meta) if meta.is_dir() => dir_size(&entry.path()),
+            Ok(meta) => meta.len(),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn abandoned_upload_gives_back_its_space_and_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let usage = StorageUsage::default();
+        let path = dir.path().join("paper.pdf");
+
+        assert!(usage.try_reserve(10, 100));
+        let mut pending = PendingUpload::new(&usage, 10);
+        std::fs::write(&path, b"%PDF").unwrap();
+        pending.stored_at(&path);
+        drop(pending);
+        assert_eq!(usage.current(), 0);
+        assert!(!path.exists());
+
+        assert!(usage.try_reserve(10, 100));
+        let mut pending = PendingUpload::new(&usage, 10);
+        std::fs::write(&path, b"%PDF").unwrap();
+        pending.stored_at(&path);
+        pending.keep();
+        assert_eq!(usage.current(), 10);
+        assert!(path.exists());
+    }
+
+    #[test]
+    fn traversal_filename_stays_inside_upload_folder() {
+        let folder = Path::new("/srv/uploads");
+        for scheme in [UploadNaming::IdAndFilename, UploadNaming::IdOnly] {
+            let name = upload_file_name(scheme, "20240101_abcd1234", "../../evil .pdf");
+            assert!(!name.contains(['/', '\\']), "{}", name);
+            assert!(!name.starts_with('.'), "{}", name);
+            assert_eq!(folder.join(&name).parent(), Some(folder));
+        }
+        assert_eq!(
+            upload_file_name(
+                UploadNaming::IdAndFilename,
+                "20240101_abcd1234",
+                "../../evil .pdf"
+            ),
+            "20240101_abcd1234_evil_.pdf"
+        );
+    }
+}
@@ -12,17 +12,10 @@ pub fn get_tera() -> &'static Tera {
                 std::fs::read_dir(template_dir)
                     .unwrap()
                     .filter_map(Result::ok)
-                    .filter(|e| e.path().extension().map_or(false, |ext| ext == "html"))
+                    .filter(|e| e.path().extension().is_some_and(|ext| ext == "html"))
                     .map(|e| {
-                        (
-                            e.path()
-                                .file_name()
-                                .unwrap()
-                                .to_str()
-                                .unwrap()
-                                .to_string(),
-                            e.path(),
-                        )
+                        let name = e.file_name().to_str().unwrap().to_string();
+                        (e.path(), Some(name))
                     }),
             )
             .expect("Failed to load templates");
@@ -0,0 +1,118 @@
+// Fixtures shared by the unit tests: a default configuration, an AppState
+// whose database is never reachable, and small hand-built PDFs.
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+use crate::config::Config;
+use crate::state::AppState;
+
+/// The configuration an empty environment gives, apart from the API key.
+pub fn config() -> Config {
+    static DEFAULTS: OnceLock<Config> = OnceLock::new();
+    DEFAULTS
+        .get_or_init(|| {
+            std::env::set_var("CLAUDE_API_KEY", "test-key");
+            Config::from_env().expect("default configuration")
+        })
+        .clone()
+}
+
+/// State for handler tests. Database calls fail quickly, so tests exercise
+/// the paths that are decided before anything is stored.
+pub fn app_state(config: Config) -> Arc<AppState> {
+    let pool = sqlx::postgres::PgPoolOptions::new()
+        .acquire_timeout(Duration::from_millis(200))
+        .connect_lazy("postgres://cuadrada@127.0.0.1:1/cuadrada")
+        .expect("lazy pool");
+    Arc::new(AppState {
+        pool: Arc::new(pool),
+        config: Arc::new(config),
+        http: reqwest::Client::new(),
+        events: crate::events::channel(),
+        submission_locks: Default::default(),
+        in_flight_uploads: Default::default(),
+        maintenance: Default::default(),
+        storage_usage: Default::default(),
+        batches: None,
+        review_queue: Default::default(),
+        status_cache: crate::state::StatusCache::new(Duration::ZERO),
+        extraction_pool: crate::agents::ExtractionPool::new(1),
+    })
+}
+
+/// Serializes `objects` (numbered from 1, the first being the catalog) with
+/// a valid cross-reference table; `trailer` is appended to the trailer
+/// dictionary.
+pub fn pdf(objects: &[String], trailer: &str) -> Vec<u8> {
+    let mut pdf = b"%PDF-1.4\n".to_vec();
+    let mut offsets = Vec::new();
+    for (i, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        pdf.extend_from_slice(format!("{} 0 obj\n{}\nendobj\n", i + 1, object).as_bytes());
+    }
+    let xref = pdf.len();
+    pdf.extend_from_slice(
+        format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).as_bytes(),
+    );
+    for offset in offsets {
+        pdf.extend_from_slice(format!("{:010} 00000 n \n", offset).as_bytes());
+    }
+    pdf.extend_from_slice(
+        format!(
+            "trailer\n<< /Size {} /Root 1 0 R {}>>\nstartxref\n{}\n%%EOF\n",
+            objects.len() + 1,
+            trailer,
+            xref
+        )
+        .as_bytes(),
+    );
+    pdf
+}
+
+/// A one-page PDF showing `text` (ASCII, no parentheses) in Helvetica.
+pub fn text_pdf(text: &str) -> Vec<u8> {
+    let content = format!("BT /F1 10 Tf 20 700 Td ({}) Tj ET", text);
+    pdf(
+        &[
+            "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+            "<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_string(),
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 612 792] \
+             /Resources << /Font << /F1 5 0 R >> >> /Contents 4 0 R >>"
+                .to_string(),
+            format!(
+                "<< /Length {} >>\nstream\n{}\nendstream",
+                content.len(),
+                content
+            ),
+            "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica >>".to_string(),
+        ],
+        "",
+    )
+}
+
+/// English prose long enough to pass the minimum text check.
+pub fn english_pdf() -> Vec<u8> {
+    text_pdf(
+        &"This paper shows that the method is sound and that the results hold for all of \
+          the cases we have tested in the study. "
+            .repeat(3),
+    )
+}
+
+/// A one-page PDF under the standard security handler whose user password
+/// is not empty, so it cannot be opened without one.
+pub fn encrypted_pdf() -> Vec<u8> {
+    pdf(
+        &[
+            "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+            "<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_string(),
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 612 792] >>".to_string(),
+            format!(
+                "<< /Filter /Standard /V 1 /R 2 /P -4 /O <{}> /U <{}> >>",
+                "11".repeat(32),
+                "22".repeat(32)
+            ),
+        ],
+        &format!("/Encrypt 4 0 R /ID [<{0}> <{0}>] ", "ab".repeat(16)),
+    )
+}